@@ -1,25 +1,353 @@
-use std::{error::Error, path::PathBuf};
+use std::{
+    collections::{BTreeSet, HashSet},
+    error::Error,
+    path::PathBuf,
+};
 
-use unity_rs::common::assembly::UnityAssembly;
+use codegen::{Impl, Scope};
+use unity_rs::{
+    common::{assembly::UnityAssembly, field::UnityField, method::UnityMethod},
+    runtime::FerrexRuntime,
+};
 
-use crate::core;
+use crate::{core, errors::DynErr, warn};
 
-pub fn run(asm: &UnityAssembly) -> Result<(), Box<dyn Error>> {
+use super::{
+    names,
+    types::{self, ClassEntry, ClassMap, TypeKind},
+};
+
+/// what got written for one assembly
+#[derive(Debug, Default)]
+pub struct Generated {
+    pub classes: usize,
+    /// other assemblies the wrappers refer to, their changes invalidate this module
+    pub references: BTreeSet<String>,
+}
+
+/// writes `<assembly>/mod.rs`, holding a wrapper struct for every class of `asm`
+pub fn run(asm: &UnityAssembly, classes: &ClassMap) -> Result<Generated, Box<dyn Error>> {
     let runtime = core::get_runtime()?;
-    let path = PathBuf::from("Ferrex").join("Bindings");
-    
-    let name = asm.get_name(runtime)?.to_lowercase().replace(".", "_").replace("-", "_");
+    let path = PathBuf::from("Ferrex").join("Bindings").join("src");
+
+    let assembly = asm.get_name(runtime)?;
+    let name = names::module_name(&assembly);
     let folder = path.join(&name);
 
-    let mod_path = folder.join("mod.rs");
+    let mut entries: Vec<&ClassEntry> = classes
+        .values()
+        .filter(|e| e.assembly == assembly)
+        .collect();
+    entries.sort_by(|a, b| (&a.modules, &a.struct_name).cmp(&(&b.modules, &b.struct_name)));
+
+    let mut references = BTreeSet::new();
+    let mut scope = Scope::new();
+    scope.raw("// This file has been generated by Ferrex");
+    scope.import("crate::prelude", "*");
+
+    for entry in entries.iter() {
+        let module = module_scope(&mut scope, &entry.modules);
+
+        if let Err(e) = emit_class(module, entry, classes, &mut references, runtime) {
+            warn!(
+                "Skipping bindings for {}: {}",
+                entry.full_name(classes),
+                e.to_string()
+            )?;
+        }
+    }
+
+    std::fs::create_dir_all(&folder)?;
+    std::fs::write(folder.join("mod.rs"), scope.to_string())?;
+
+    references.remove(&assembly);
+
+    Ok(Generated {
+        classes: entries.len(),
+        references,
+    })
+}
+
+/// walks down to the scope of a namespace, creating the modules on the way
+fn module_scope<'a>(scope: &'a mut Scope, modules: &[String]) -> &'a mut Scope {
+    match modules.split_first() {
+        Some((first, rest)) => {
+            let module = scope.get_or_new_module(first);
+            module.vis("pub");
+            module.import("crate::prelude", "*");
+            module_scope(module.scope(), rest)
+        }
+        None => scope,
+    }
+}
+
+fn emit_class(
+    scope: &mut Scope,
+    entry: &ClassEntry,
+    classes: &ClassMap,
+    references: &mut BTreeSet<String>,
+    runtime: &FerrexRuntime,
+) -> Result<(), DynErr> {
+    let full_name = entry.full_name(classes);
+    let mut members = Impl::new(&entry.struct_name);
+    let mut used = HashSet::new();
+
+    let resolve = match entry.declaring.and_then(|d| classes.get(&d)) {
+        Some(outer) => format!(
+            "resolve_nested(&CLASS, {}::class()?, \"{}\")",
+            outer.rust_path(),
+            entry.name
+        ),
+        None => format!(
+            "resolve_class(&CLASS, \"{}\", \"{}\", \"{}\")",
+            entry.assembly, entry.namespace, entry.name
+        ),
+    };
+
+    members
+        .new_fn("class")
+        .vis("pub")
+        .ret("Result<UnityClass, RuntimeError>")
+        .line("static CLASS: OnceLock<UnityClass> = OnceLock::new();")
+        .line(resolve);
+    used.insert("class".to_string());
+
+    for method in entry.class.get_methods(runtime)? {
+        // methods with types we can't map are skipped, the rest of the class is still usable
+        let _ = emit_method(&mut members, &method, entry, classes, &mut used, references, runtime);
+    }
+
+    for field in entry.class.get_fields(runtime)? {
+        let _ = emit_field(&mut members, &field, classes, &mut used, references, runtime);
+    }
+
+    scope
+        .new_struct(&entry.struct_name)
+        .vis("pub")
+        .doc(&format!("`{}` from `{}`", full_name, entry.assembly))
+        .derive("Debug")
+        .derive("Clone")
+        .derive("Copy")
+        .repr("transparent")
+        .field("pub object", "UnityObject");
+
+    scope.push_impl(members);
+
+    let to_managed = match entry.valuetype {
+        true => "Ok(runtime()?.unbox_object(&self.object)?.inner)",
+        false => "Ok(self.object.inner)",
+    };
+
+    scope
+        .new_impl(&entry.struct_name)
+        .impl_trait("ToManaged")
+        .new_fn("to_managed")
+        .arg_ref_self()
+        .ret("Result<*mut c_void, RuntimeError>")
+        .line(to_managed);
+
+    // value types are boxed by runtime_invoke, so they are never null
+    let (target, body) = match entry.valuetype {
+        true => (
+            entry.struct_name.clone(),
+            format!(
+                "value.map(|object| {} {{ object }}).ok_or(RuntimeError::ReturnedNull(\"from_managed\"))",
+                entry.struct_name
+            ),
+        ),
+        false => (
+            format!("Option<{}>", entry.struct_name),
+            format!("Ok(value.map(|object| {} {{ object }}))", entry.struct_name),
+        ),
+    };
+
+    scope
+        .new_impl(&target)
+        .impl_trait("FromManaged")
+        .new_fn("from_managed")
+        .arg("value", "Option<UnityObject>")
+        .ret("Result<Self, RuntimeError>")
+        .line(body);
+
+    Ok(())
+}
+
+fn emit_method(
+    members: &mut Impl,
+    method: &UnityMethod,
+    entry: &ClassEntry,
+    classes: &ClassMap,
+    used: &mut HashSet<String>,
+    references: &mut BTreeSet<String>,
+    runtime: &FerrexRuntime,
+) -> Result<(), DynErr> {
+    let signature = method.get_signature(runtime)?;
+
+    // constructors need an allocated object, abstract methods can't be invoked directly
+    // and generic definitions have no code until they're instantiated
+    if signature.name.starts_with('.') || signature.is_abstract || signature.generic_arity > 0 {
+        return Ok(());
+    }
+
+    let ret = types::resolve(&signature.return_type, classes, runtime)?;
+
+    let mut params = Vec::new();
+    let mut param_names = HashSet::new();
+    for param in signature.parameters.iter() {
+        let kind = types::resolve(&param.ty, classes, runtime)?;
+        let name = names::unique(names::snake_case(&param.name), &mut param_names);
+        params.push((name, kind, param.ty.get_name(runtime)?));
+    }
+
+    references.extend(
+        params
+            .iter()
+            .map(|(_, kind, _)| kind)
+            .chain([&ret])
+            .filter_map(|kind| kind.assembly().map(str::to_string)),
+    );
+
+    let name = names::unique(names::snake_case(&signature.name), used);
+    let param_types: Vec<String> = params.iter().map(|(_, _, ty)| format!("\"{}\"", ty)).collect();
+
+    let function = members.new_fn(&name).vis("pub").doc(&format!(
+        "`{} {}({})`",
+        signature.return_type.get_name(runtime)?,
+        signature.name,
+        params
+            .iter()
+            .map(|(_, _, ty)| ty.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+
+    if !signature.is_static {
+        function.arg_ref_self();
+    }
+
+    for (name, kind, _) in params.iter() {
+        function.arg(name, kind.param_type());
+    }
+
+    function
+        .ret(format!("Result<{}, RuntimeError>", ret.return_type()))
+        .line("static METHOD: OnceLock<UnityMethod> = OnceLock::new();")
+        .line(format!(
+            "let __method = resolve_method(&METHOD, Self::class()?, \"{}\", &[{}])?;",
+            signature.name,
+            param_types.join(", ")
+        ));
+
+    let object = match (signature.is_static, entry.valuetype) {
+        (true, _) => "None",
+        (false, true) => {
+            function.line("let __this = runtime()?.unbox_object(&self.object)?;");
+            "Some(&__this)"
+        }
+        (false, false) => "Some(&self.object)",
+    };
+
+    let args = match params.is_empty() {
+        true => "None",
+        false => {
+            function.line(format!(
+                "let mut __args = vec![{}];",
+                params
+                    .iter()
+                    .map(|(name, _, _)| format!("{}.to_managed()?", name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+            "Some(&mut __args)"
+        }
+    };
+
+    function.line(format!(
+        "FromManaged::from_managed(__method.invoke({}, {}, runtime()?)?)",
+        object, args
+    ));
+
+    Ok(())
+}
+
+fn emit_field(
+    members: &mut Impl,
+    field: &UnityField,
+    classes: &ClassMap,
+    used: &mut HashSet<String>,
+    references: &mut BTreeSet<String>,
+    runtime: &FerrexRuntime,
+) -> Result<(), DynErr> {
+    let field_name = field.get_name(runtime)?;
+    let kind = types::resolve(&field.get_type(runtime)?, classes, runtime)?;
+
+    // value types without a wrapper have no size we could read them with
+    if matches!(kind, TypeKind::Raw | TypeKind::Void) {
+        return Ok(());
+    }
+
+    if let TypeKind::Class { valuetype: true, .. } = kind {
+        return Ok(());
+    }
+
+    if let Some(assembly) = kind.assembly() {
+        references.insert(assembly.to_string());
+    }
+
+    let is_static = field.is_static(runtime)?;
+    let object = match is_static {
+        true => "None",
+        false => "Some(&self.object)",
+    };
+
+    let getter = names::unique(names::snake_case(&field_name), used);
+    let setter = names::unique(format!("set_{}", names::snake_case(&field_name)), used);
+    let resolve = format!(
+        "let __field = resolve_field(&FIELD, Self::class()?, \"{}\")?;",
+        field_name
+    );
+
+    let read = match (kind.is_reference(), is_static) {
+        (true, _) => format!("FromManaged::from_managed(read_reference(&__field, {})?)", object),
+        (false, true) => "__field.get_static_value(runtime()?)".to_string(),
+        (false, false) => "__field.get_value(&self.object, runtime()?)".to_string(),
+    };
+
+    let write = match is_static {
+        true => "__field.set_static_value(value.to_managed()?, runtime()?)",
+        false => "__field.set_value(&self.object, value.to_managed()?, runtime()?)",
+    };
+
+    let function = members
+        .new_fn(&getter)
+        .vis("pub")
+        .doc(&format!("reads `{}`", field_name));
+
+    if !is_static {
+        function.arg_ref_self();
+    }
+
+    function
+        .ret(format!("Result<{}, RuntimeError>", kind.return_type()))
+        .line("static FIELD: OnceLock<UnityField> = OnceLock::new();")
+        .line(&resolve)
+        .line(read);
+
+    let function = members
+        .new_fn(&setter)
+        .vis("pub")
+        .doc(&format!("writes `{}`", field_name));
 
-    let mut file = std::fs::OpenOptions::new()
-        .write(true)
-        .append(true)
-        .create(true)
-        .open(&mod_path)?;
+    if !is_static {
+        function.arg_ref_self();
+    }
 
-    
+    function
+        .arg("value", kind.param_type())
+        .ret("Result<(), RuntimeError>")
+        .line("static FIELD: OnceLock<UnityField> = OnceLock::new();")
+        .line(&resolve)
+        .line(write);
 
     Ok(())
-}
\ No newline at end of file
+}
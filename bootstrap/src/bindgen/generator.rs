@@ -1,29 +1,244 @@
 use std::{
     error::Error,
-    fs::{self, File},
-    io::Write,
-    path::PathBuf,
-    process::Command,
+    fs::{self},
+    path::{Path, PathBuf},
 };
 
-use crate::core;
+use crate::{core, log};
 
-pub fn run() -> Result<(), Box<dyn Error>> {
-    let runtime = core::get_runtime()?;
+use super::{
+    classes, manifest::{AssemblyEntry, Manifest}, manifest, names, types,
+};
 
-    //let class = runtime.open_assembly("UnityEngine.CoreModule".to_string())?.get_class(runtime, "UnityEngine", "Application")?;
+/// shared helpers of the bindings crate, every generated module imports its prelude
+const LIB_RS: &str = r#"// This file has been generated by Ferrex
+#![allow(non_snake_case, non_camel_case_types, dead_code, unused_imports, clippy::all)]
 
-    let path = PathBuf::from("Ferrex").join("Bindings");
+use prelude::*;
+use unity_rs::runtime::FerrexRuntime;
+
+pub(crate) mod prelude {
+    pub use std::{ffi::c_void, sync::OnceLock};
+
+    pub use unity_rs::{
+        common::{class::UnityClass, field::UnityField, method::UnityMethod, object::UnityObject},
+        runtime::{Runtime, RuntimeError},
+    };
+
+    pub use crate::{
+        read_reference, resolve_class, resolve_field, resolve_method, resolve_nested, runtime,
+        FromManaged, ToManaged,
+    };
+}
+
+static RUNTIME: OnceLock<FerrexRuntime> = OnceLock::new();
+
+/// the runtime the bindings talk to, created on first use
+pub fn runtime() -> Result<&'static FerrexRuntime, RuntimeError> {
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+
+    let runtime = unity_rs::runtime::get_runtime()?;
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+pub fn resolve_class(
+    cache: &OnceLock<UnityClass>,
+    assembly: &str,
+    namespace: &str,
+    name: &str,
+) -> Result<UnityClass, RuntimeError> {
+    if let Some(class) = cache.get() {
+        return Ok(*class);
+    }
+
+    let runtime = runtime()?;
+    for asm in runtime.get_assemblies()? {
+        if asm.get_name(runtime)? == assembly {
+            let class = asm.get_class(namespace, name, runtime)?;
+            return Ok(*cache.get_or_init(|| class));
+        }
+    }
+
+    Err(RuntimeError::Passthrough(format!(
+        "Assembly {} not found",
+        assembly
+    )))
+}
+
+pub fn resolve_nested(
+    cache: &OnceLock<UnityClass>,
+    outer: UnityClass,
+    name: &str,
+) -> Result<UnityClass, RuntimeError> {
+    if let Some(class) = cache.get() {
+        return Ok(*class);
+    }
+
+    let runtime = runtime()?;
+    for class in outer.get_nested_types(runtime)? {
+        if class.get_name(runtime)? == name {
+            return Ok(*cache.get_or_init(|| class));
+        }
+    }
 
-    if path.exists() {
-        fs::remove_dir_all(&path)?;
+    Err(RuntimeError::Passthrough(format!(
+        "Nested type {} not found",
+        name
+    )))
+}
+
+/// finds a method by name and parameter type names, so overloads resolve to the right one
+pub fn resolve_method(
+    cache: &OnceLock<UnityMethod>,
+    class: UnityClass,
+    name: &str,
+    params: &[&str],
+) -> Result<UnityMethod, RuntimeError> {
+    if let Some(method) = cache.get() {
+        return Ok(*method);
+    }
+
+    let runtime = runtime()?;
+    for method in class.get_methods(runtime)? {
+        if method.get_name(runtime)? != name {
+            continue;
+        }
+
+        let types = method.get_param_types(runtime)?;
+        if types.len() != params.len() {
+            continue;
+        }
+
+        let mut matches = true;
+        for (ty, param) in types.iter().zip(params) {
+            if ty.get_name(runtime)? != *param {
+                matches = false;
+                break;
+            }
+        }
+
+        if matches {
+            return Ok(*cache.get_or_init(|| method));
+        }
+    }
+
+    Err(RuntimeError::Passthrough(format!("Method {} not found", name)))
+}
+
+pub fn resolve_field(
+    cache: &OnceLock<UnityField>,
+    class: UnityClass,
+    name: &str,
+) -> Result<UnityField, RuntimeError> {
+    if let Some(field) = cache.get() {
+        return Ok(*field);
     }
 
-    fs::create_dir(&path)?;
+    let field = class.get_field(name, runtime()?)?;
+    Ok(*cache.get_or_init(|| field))
+}
+
+/// reads a field holding an object reference, `object` is None for statics
+pub fn read_reference(
+    field: &UnityField,
+    object: Option<&UnityObject>,
+) -> Result<Option<UnityObject>, RuntimeError> {
+    let value: *mut c_void = match object {
+        Some(object) => field.get_value(object, runtime()?)?,
+        None => field.get_static_value(runtime()?)?,
+    };
+
+    match value.is_null() {
+        true => Ok(None),
+        false => Ok(Some(UnityObject { inner: value })),
+    }
+}
+
+/// converts an argument into the pointer runtime_invoke expects
+pub trait ToManaged {
+    fn to_managed(&self) -> Result<*mut c_void, RuntimeError>;
+}
+
+/// converts the boxed return value of runtime_invoke
+pub trait FromManaged: Sized {
+    fn from_managed(value: Option<UnityObject>) -> Result<Self, RuntimeError>;
+}
+
+macro_rules! primitive {
+    ($($ty:ty),*) => {
+        $(
+            impl ToManaged for $ty {
+                fn to_managed(&self) -> Result<*mut c_void, RuntimeError> {
+                    Ok(self as *const $ty as *mut c_void)
+                }
+            }
+
+            impl FromManaged for $ty {
+                fn from_managed(value: Option<UnityObject>) -> Result<Self, RuntimeError> {
+                    let object = value.ok_or(RuntimeError::ReturnedNull("from_managed"))?;
+                    let unboxed = runtime()?.unbox_object(&object)?;
+                    Ok(unsafe { *unboxed.inner.cast::<$ty>() })
+                }
+            }
+        )*
+    };
+}
 
-    let cargo_toml_path = path.join("Cargo.toml");
+primitive!(bool, i8, u8, i16, u16, i32, u32, i64, u64, f32, f64, isize, usize);
 
-    let content = "# This file has been generated by Ferrex
+impl ToManaged for &str {
+    fn to_managed(&self) -> Result<*mut c_void, RuntimeError> {
+        Ok(runtime()?.new_string(self)?.inner)
+    }
+}
+
+impl ToManaged for UnityObject {
+    fn to_managed(&self) -> Result<*mut c_void, RuntimeError> {
+        Ok(self.inner)
+    }
+}
+
+impl ToManaged for *mut c_void {
+    fn to_managed(&self) -> Result<*mut c_void, RuntimeError> {
+        Ok(*self)
+    }
+}
+
+impl<T: ToManaged> ToManaged for &T {
+    fn to_managed(&self) -> Result<*mut c_void, RuntimeError> {
+        (*self).to_managed()
+    }
+}
+
+impl FromManaged for () {
+    fn from_managed(_: Option<UnityObject>) -> Result<Self, RuntimeError> {
+        Ok(())
+    }
+}
+
+impl FromManaged for Option<UnityObject> {
+    fn from_managed(value: Option<UnityObject>) -> Result<Self, RuntimeError> {
+        Ok(value)
+    }
+}
+
+impl FromManaged for Option<String> {
+    fn from_managed(value: Option<UnityObject>) -> Result<Self, RuntimeError> {
+        match value {
+            Some(object) => {
+                let string = unity_rs::common::string::UnityString { inner: object.inner };
+                Ok(Some(string.to_string(runtime()?)?))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+"#;
+
+const CARGO_TOML: &str = "# This file has been generated by Ferrex
 [package]
 name = \"bindings\"
 version = \"0.1.0\"
@@ -34,12 +249,21 @@ edition = \"2021\"
 [dependencies]
 unity-rs = { path = \"../unity\" }";
 
-    fs::write(cargo_toml_path, content)?;
+/// only touches files whose content changed, so cargo doesn't rebuild a mod crate for nothing
+fn write_if_changed(path: &Path, content: &str) -> Result<(), Box<dyn Error>> {
+    if fs::read_to_string(path).map_or(true, |old| old != content) {
+        fs::write(path, content)?;
+    }
 
-    let path = path.join("src");
-    fs::create_dir(&path)?;
+    Ok(())
+}
+
+pub fn run() -> Result<(), Box<dyn Error>> {
+    let runtime = core::get_runtime()?;
 
-    let lib_path = path.join("lib.rs");
+    let path = PathBuf::from("Ferrex").join("Bindings");
+    let manifest_path = path.join("manifest.toml");
+    let src = path.join("src");
 
     let mut assemblies = runtime.get_assemblies()?;
 
@@ -50,24 +274,75 @@ unity-rs = { path = \"../unity\" }";
             .cmp(&b.get_name(runtime).unwrap().to_lowercase())
     });
 
+    let fingerprints = manifest::fingerprints(&assemblies, runtime)?;
+
+    let mut manifest = match src.exists() {
+        true => Manifest::load(&manifest_path),
+        false => Manifest::default(),
+    };
+
+    let outdated = manifest.outdated(&fingerprints);
+    let removed: Vec<String> = manifest
+        .assemblies
+        .keys()
+        .filter(|name| !fingerprints.contains_key(*name))
+        .cloned()
+        .collect();
+
+    if outdated.is_empty() && removed.is_empty() {
+        log!("Bindings are up to date")?;
+        return Ok(());
+    }
+
+    fs::create_dir_all(&src)?;
+    write_if_changed(&path.join("Cargo.toml"), CARGO_TOML)?;
+
+    for name in removed.iter() {
+        let folder = src.join(names::module_name(name));
+        if folder.exists() {
+            fs::remove_dir_all(folder)?;
+        }
+
+        manifest.assemblies.remove(name);
+    }
+
+    let mut lib = LIB_RS.to_string();
     for asm in assemblies.iter() {
-        let name = asm
-            .get_name(runtime)?
-            .to_lowercase()
-            .replace(".", "_")
-            .replace("-", "_");
-        let folder = path.join(&name);
+        lib.push_str(&format!("pub mod {};\n", names::module_name(&asm.get_name(runtime)?)));
+    }
 
-        let mut file = std::fs::OpenOptions::new()
-            .write(true)
-            .append(true)
-            .create(true)
-            .open(&lib_path)?;
+    write_if_changed(&src.join("lib.rs"), &lib)?;
 
-        file.write_all(format!("pub mod {};\n", name).as_bytes())?;
+    // the class map spans every assembly, wrappers refer to types across assembly boundaries
+    let classes = types::collect(&assemblies, runtime)?;
 
-        fs::create_dir(folder)?;
+    let mut count = 0;
+    for asm in assemblies.iter() {
+        let name = asm.get_name(runtime)?;
+        if !outdated.contains(&name) {
+            continue;
+        }
+
+        let generated = classes::run(asm, &classes)?;
+        count += generated.classes;
+
+        manifest.assemblies.insert(
+            name.clone(),
+            AssemblyEntry {
+                fingerprint: fingerprints[&name].clone(),
+                references: generated.references,
+            },
+        );
     }
 
+    manifest.generator = env!("CARGO_PKG_VERSION").to_string();
+    manifest.save(&manifest_path)?;
+
+    log!(
+        "Generated bindings for {} classes in {} assemblies",
+        count,
+        outdated.len()
+    )?;
+
     Ok(())
 }
@@ -0,0 +1,259 @@
+//! fingerprints of the assemblies the bindings were generated from
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use unity_rs::{common::assembly::UnityAssembly, runtime::FerrexRuntime, utils::path};
+
+use crate::errors::DynErr;
+
+/// identifies one build of an assembly
+///
+/// mono gives us the version and mvid, il2cpp has neither, so it falls back to a file hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub mvid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssemblyEntry {
+    pub fingerprint: Fingerprint,
+    /// assemblies whose wrappers the generated module refers to
+    #[serde(default)]
+    pub references: BTreeSet<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    /// the ferrex version that generated the bindings, the output changes between versions
+    pub generator: String,
+    #[serde(default)]
+    pub assemblies: BTreeMap<String, AssemblyEntry>,
+}
+
+impl Manifest {
+    /// loads the manifest, an unreadable or outdated one is treated as missing
+    pub fn load(path: &Path) -> Manifest {
+        let manifest = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| toml::from_str::<Manifest>(&content).ok());
+
+        match manifest {
+            Some(manifest) if manifest.generator == env!("CARGO_PKG_VERSION") => manifest,
+            _ => Manifest::default(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), DynErr> {
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// the assemblies that have to be regenerated, including the ones referring to a changed assembly
+    pub fn outdated(&self, current: &BTreeMap<String, Fingerprint>) -> BTreeSet<String> {
+        let mut outdated: BTreeSet<String> = current
+            .iter()
+            .filter(|(name, fingerprint)| {
+                self.assemblies
+                    .get(*name)
+                    .is_none_or(|entry| &entry.fingerprint != *fingerprint)
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        let removed: BTreeSet<String> = self
+            .assemblies
+            .keys()
+            .filter(|name| !current.contains_key(*name))
+            .cloned()
+            .collect();
+
+        loop {
+            let dependents: Vec<String> = self
+                .assemblies
+                .iter()
+                .filter(|(name, _)| current.contains_key(*name) && !outdated.contains(*name))
+                .filter(|(_, entry)| {
+                    entry
+                        .references
+                        .iter()
+                        .any(|r| outdated.contains(r) || removed.contains(r))
+                })
+                .map(|(name, _)| name.clone())
+                .collect();
+
+            if dependents.is_empty() {
+                break;
+            }
+
+            outdated.extend(dependents);
+        }
+
+        outdated
+    }
+}
+
+fn file_hash(path: &Path) -> Result<String, DynErr> {
+    let hash = Sha256::digest(fs::read(path)?);
+
+    Ok(hash.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// the global metadata holds every il2cpp assembly, so it changes whenever one of them does
+fn metadata_hash() -> Result<String, DynErr> {
+    let data_path = path::get_data_path(&std::env::current_exe()?)?;
+
+    file_hash(
+        &data_path
+            .join("il2cpp_data")
+            .join("Metadata")
+            .join("global-metadata.dat"),
+    )
+}
+
+/// fingerprints every assembly, keyed by its name
+pub fn fingerprints(
+    assemblies: &[UnityAssembly],
+    runtime: &FerrexRuntime,
+) -> Result<BTreeMap<String, Fingerprint>, DynErr> {
+    let mut fingerprints = BTreeMap::new();
+    let mut metadata = None;
+
+    for asm in assemblies {
+        let image = asm.get_image(runtime)?;
+        let version = asm.get_version(runtime).ok();
+        let mvid = image.get_guid(runtime).ok();
+
+        let hash = match mvid {
+            Some(_) => None,
+            None => {
+                let filename = image.get_filename(runtime)?;
+                let file = Path::new(&filename);
+
+                match file.is_file() {
+                    true => Some(file_hash(file)?),
+                    false => {
+                        if metadata.is_none() {
+                            metadata = Some(metadata_hash()?);
+                        }
+
+                        metadata.clone()
+                    }
+                }
+            }
+        };
+
+        fingerprints.insert(
+            asm.get_name(runtime)?,
+            Fingerprint {
+                version,
+                mvid,
+                hash,
+            },
+        );
+    }
+
+    Ok(fingerprints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprint(mvid: &str) -> Fingerprint {
+        Fingerprint {
+            version: None,
+            mvid: Some(mvid.to_string()),
+            hash: None,
+        }
+    }
+
+    /// a manifest of `(name, mvid, references)`
+    fn manifest(assemblies: &[(&str, &str, &[&str])]) -> Manifest {
+        Manifest {
+            generator: env!("CARGO_PKG_VERSION").to_string(),
+            assemblies: assemblies
+                .iter()
+                .map(|(name, mvid, references)| {
+                    let entry = AssemblyEntry {
+                        fingerprint: fingerprint(mvid),
+                        references: references.iter().map(|r| r.to_string()).collect(),
+                    };
+
+                    (name.to_string(), entry)
+                })
+                .collect(),
+        }
+    }
+
+    fn current(assemblies: &[(&str, &str)]) -> BTreeMap<String, Fingerprint> {
+        assemblies
+            .iter()
+            .map(|(name, mvid)| (name.to_string(), fingerprint(mvid)))
+            .collect()
+    }
+
+    fn names(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn nothing_is_outdated_when_nothing_changed() {
+        let manifest = manifest(&[("Game", "1", &["Engine"]), ("Engine", "2", &[])]);
+
+        assert!(manifest.outdated(&current(&[("Game", "1"), ("Engine", "2")])).is_empty());
+    }
+
+    #[test]
+    fn changed_and_new_assemblies_are_outdated() {
+        let manifest = manifest(&[("Game", "1", &[]), ("Engine", "2", &[])]);
+        let outdated = manifest.outdated(&current(&[("Game", "3"), ("Engine", "2"), ("Mod", "4")]));
+
+        assert_eq!(outdated, names(&["Game", "Mod"]));
+    }
+
+    #[test]
+    fn dependents_of_a_changed_assembly_are_outdated() {
+        let manifest = manifest(&[
+            ("Engine", "1", &[]),
+            ("Game", "2", &["Engine"]),
+            ("Mod", "3", &["Game"]),
+            ("Other", "4", &[]),
+        ]);
+
+        let outdated = manifest.outdated(&current(&[("Engine", "5"), ("Game", "2"), ("Mod", "3"), ("Other", "4")]));
+
+        assert_eq!(outdated, names(&["Engine", "Game", "Mod"]));
+    }
+
+    #[test]
+    fn dependents_of_a_removed_assembly_are_outdated() {
+        let manifest = manifest(&[("Engine", "1", &[]), ("Game", "2", &["Engine"])]);
+
+        assert_eq!(manifest.outdated(&current(&[("Game", "2")])), names(&["Game"]));
+    }
+
+    #[test]
+    fn file_hash_is_zero_padded_hex() {
+        let file = std::env::temp_dir().join(format!("ferrex-file-hash-{}", std::process::id()));
+        fs::write(&file, b"").unwrap();
+
+        let hash = file_hash(&file);
+        let _ = fs::remove_file(&file);
+
+        assert_eq!(
+            hash.unwrap(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
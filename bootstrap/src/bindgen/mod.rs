@@ -1,2 +1,5 @@
 pub mod generator;
-pub mod classes;
\ No newline at end of file
+pub mod classes;
+pub mod manifest;
+pub mod names;
+pub mod types;
\ No newline at end of file
@@ -0,0 +1,79 @@
+//! turns managed names into valid rust identifiers
+
+use std::collections::HashSet;
+
+/// keywords that can't be used as identifiers, raw identifiers are avoided since
+/// `self`, `Self`, `super` and `crate` can't be raw either
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+    "try", "type", "union", "unsafe", "use", "where", "while", "yield", "abstract", "become",
+    "do", "final", "macro", "override", "priv", "typeof", "unsized", "virtual",
+];
+
+/// the module name of an assembly, e.g. `UnityEngine.CoreModule` -> `unityengine_coremodule`
+pub fn module_name(assembly: &str) -> String {
+    sanitize(&assembly.to_lowercase().replace(['.', '-'], "_"))
+}
+
+/// replaces anything that isn't valid in an identifier, and escapes keywords
+pub fn sanitize(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+
+    if KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+
+    ident
+}
+
+/// `get_targetFrameRate` -> `get_target_frame_rate`
+pub fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev = if i > 0 { chars.get(i - 1) } else { None };
+            let next = chars.get(i + 1);
+
+            let boundary = match prev {
+                Some(p) if p.is_lowercase() || p.is_ascii_digit() => true,
+                Some(p) if p.is_uppercase() => next.is_some_and(|n| n.is_lowercase()),
+                _ => false,
+            };
+
+            if boundary && !snake.ends_with('_') {
+                snake.push('_');
+            }
+
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(*c);
+        }
+    }
+
+    sanitize(&snake)
+}
+
+/// appends a counter to `name` until it no longer collides with anything in `used`
+pub fn unique(name: String, used: &mut HashSet<String>) -> String {
+    let mut candidate = name.clone();
+    let mut counter = 1;
+
+    while used.contains(&candidate) {
+        candidate = format!("{}_{}", name, counter);
+        counter += 1;
+    }
+
+    used.insert(candidate.clone());
+    candidate
+}
@@ -0,0 +1,263 @@
+//! maps managed types onto the rust types used by the generated bindings
+
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::c_void,
+};
+
+use unity_rs::{
+    common::{assembly::UnityAssembly, class::UnityClass, ty::UnityType},
+    runtime::FerrexRuntime,
+};
+
+use crate::errors::DynErr;
+
+use super::names;
+
+/// a class that gets a generated wrapper
+#[derive(Debug, Clone)]
+pub struct ClassEntry {
+    pub class: UnityClass,
+    /// the managed assembly name, used to resolve the class at runtime
+    pub assembly: String,
+    pub namespace: String,
+    pub name: String,
+    /// the class this one is nested in, if any
+    pub declaring: Option<*mut c_void>,
+    pub valuetype: bool,
+    /// module path below the assembly module, one entry per namespace segment
+    pub modules: Vec<String>,
+    pub struct_name: String,
+}
+
+impl ClassEntry {
+    /// the full managed name, `Namespace.Outer.Inner`
+    pub fn full_name(&self, classes: &ClassMap) -> String {
+        match self.declaring.and_then(|d| classes.get(&d)) {
+            Some(outer) => format!("{}.{}", outer.full_name(classes), self.name),
+            None if self.namespace.is_empty() => self.name.clone(),
+            None => format!("{}.{}", self.namespace, self.name),
+        }
+    }
+
+    /// the path of the wrapper from the root of the bindings crate
+    pub fn rust_path(&self) -> String {
+        let mut path = vec!["crate".to_string(), names::module_name(&self.assembly)];
+        path.extend(self.modules.iter().cloned());
+        path.push(self.struct_name.clone());
+        path.join("::")
+    }
+}
+
+/// every class that gets a wrapper, keyed by its class pointer
+///
+/// generic instances and arrays have their own class pointers, so they never hit this map
+pub type ClassMap = HashMap<*mut c_void, ClassEntry>;
+
+/// how a managed type is represented in the bindings
+#[derive(Debug, Clone)]
+pub enum TypeKind {
+    Void,
+    /// a blittable primitive, holds the rust type
+    Primitive(&'static str),
+    String,
+    /// a class that has a generated wrapper, `assembly` is the one it's defined in
+    Class {
+        path: String,
+        assembly: String,
+        valuetype: bool,
+    },
+    /// any other reference type, e.g. arrays and generic instances
+    Object,
+    /// byref parameters and value types without a wrapper, passed as a raw pointer
+    Raw,
+}
+
+impl TypeKind {
+    /// the type used for parameters
+    pub fn param_type(&self) -> String {
+        match self {
+            TypeKind::Void | TypeKind::Raw => "*mut c_void".to_string(),
+            TypeKind::Primitive(ty) => ty.to_string(),
+            TypeKind::String => "&str".to_string(),
+            TypeKind::Class { path, .. } => format!("&{}", path),
+            TypeKind::Object => "&UnityObject".to_string(),
+        }
+    }
+
+    /// the type used for return values and field reads
+    pub fn return_type(&self) -> String {
+        match self {
+            TypeKind::Void => "()".to_string(),
+            TypeKind::Primitive(ty) => ty.to_string(),
+            TypeKind::String => "Option<String>".to_string(),
+            TypeKind::Class {
+                path,
+                valuetype: true,
+                ..
+            } => path.clone(),
+            TypeKind::Class { path, .. } => format!("Option<{}>", path),
+            TypeKind::Object | TypeKind::Raw => "Option<UnityObject>".to_string(),
+        }
+    }
+
+    /// the assembly defining the wrapper this refers to
+    pub fn assembly(&self) -> Option<&str> {
+        match self {
+            TypeKind::Class { assembly, .. } => Some(assembly),
+            _ => None,
+        }
+    }
+
+    /// whether a field of this type is read as a reference
+    pub fn is_reference(&self) -> bool {
+        matches!(
+            self,
+            TypeKind::String
+                | TypeKind::Object
+                | TypeKind::Class {
+                    valuetype: false,
+                    ..
+                }
+        )
+    }
+}
+
+fn primitive(full_name: &str) -> Option<&'static str> {
+    Some(match full_name {
+        "System.Boolean" => "bool",
+        "System.SByte" => "i8",
+        "System.Byte" => "u8",
+        "System.Int16" => "i16",
+        "System.UInt16" => "u16",
+        "System.Char" => "u16",
+        "System.Int32" => "i32",
+        "System.UInt32" => "u32",
+        "System.Int64" => "i64",
+        "System.UInt64" => "u64",
+        "System.Single" => "f32",
+        "System.Double" => "f64",
+        "System.IntPtr" => "isize",
+        "System.UIntPtr" => "usize",
+        _ => return None,
+    })
+}
+
+/// works out how `ty` is represented in the bindings
+pub fn resolve(ty: &UnityType, classes: &ClassMap, runtime: &FerrexRuntime) -> Result<TypeKind, DynErr> {
+    if ty.is_byref(runtime)? {
+        return Ok(TypeKind::Raw);
+    }
+
+    let class = ty.get_class(runtime)?;
+    let full_name = format!(
+        "{}.{}",
+        class.get_namespace(runtime)?,
+        class.get_name(runtime)?
+    );
+
+    if full_name == "System.Void" {
+        return Ok(TypeKind::Void);
+    }
+
+    if full_name == "System.String" {
+        return Ok(TypeKind::String);
+    }
+
+    if let Some(primitive) = primitive(&full_name) {
+        return Ok(TypeKind::Primitive(primitive));
+    }
+
+    if let Some(entry) = classes.get(&class.inner) {
+        return Ok(TypeKind::Class {
+            path: entry.rust_path(),
+            assembly: entry.assembly.clone(),
+            valuetype: entry.valuetype,
+        });
+    }
+
+    match class.is_valuetype(runtime)? {
+        true => Ok(TypeKind::Raw),
+        false => Ok(TypeKind::Object),
+    }
+}
+
+/// compiler generated types and generic definitions can't be bound
+fn is_bindable(name: &str, namespace: &str) -> bool {
+    !name.contains(['`', '<', '>', '$', '='])
+        && !namespace.contains(['<', '>'])
+        && !name.is_empty()
+}
+
+/// collects every bindable class of `assemblies` and assigns it a rust path
+pub fn collect(assemblies: &[UnityAssembly], runtime: &FerrexRuntime) -> Result<ClassMap, DynErr> {
+    let mut classes = ClassMap::new();
+    let mut used: HashMap<Vec<String>, HashSet<String>> = HashMap::new();
+
+    for asm in assemblies {
+        let assembly = asm.get_name(runtime)?;
+        let image = asm.get_image(runtime)?;
+        let all = image.get_classes(runtime)?;
+
+        // nested types show up in the image as well, they are added through their declaring type
+        let mut nested = HashSet::new();
+        for class in all.iter() {
+            for inner in class.get_nested_types(runtime)? {
+                nested.insert(inner.inner);
+            }
+        }
+
+        let mut pending: Vec<(UnityClass, Option<*mut c_void>)> = all
+            .into_iter()
+            .filter(|c| !nested.contains(&c.inner))
+            .map(|c| (c, None))
+            .collect();
+
+        while let Some((class, declaring)) = pending.pop() {
+            let name = class.get_name(runtime)?;
+            let namespace = match declaring.and_then(|d| classes.get(&d)) {
+                Some(outer) => outer.namespace.clone(),
+                None => class.get_namespace(runtime)?,
+            };
+
+            if !is_bindable(&name, &namespace) {
+                continue;
+            }
+
+            let modules: Vec<String> = namespace
+                .split('.')
+                .filter(|s| !s.is_empty())
+                .map(|s| names::sanitize(&s.to_lowercase()))
+                .collect();
+
+            let struct_name = match declaring.and_then(|d| classes.get(&d)) {
+                Some(outer) => format!("{}_{}", outer.struct_name, names::sanitize(&name)),
+                None => names::sanitize(&name),
+            };
+
+            let mut key = vec![names::module_name(&assembly)];
+            key.extend(modules.iter().cloned());
+            let struct_name = names::unique(struct_name, used.entry(key).or_default());
+
+            for inner in class.get_nested_types(runtime)? {
+                pending.push((inner, Some(class.inner)));
+            }
+
+            classes.insert(
+                class.inner,
+                ClassEntry {
+                    class,
+                    assembly: assembly.clone(),
+                    namespace,
+                    name,
+                    declaring,
+                    valuetype: class.is_valuetype(runtime)?,
+                    modules,
+                    struct_name,
+                },
+            );
+        }
+    }
+
+    Ok(classes)
+}
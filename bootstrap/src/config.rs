@@ -0,0 +1,39 @@
+//! Ferrex's own settings, read from `Ferrex/Config.toml`
+
+use std::{fs, path::PathBuf, sync::OnceLock};
+
+use serde::Deserialize;
+
+use crate::warn;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// reload native mods when their library is rebuilt
+    pub hot_reload: bool,
+    /// ask on the console before loading a mod the trust policy doesn't allow, instead of skipping it
+    pub confirm_untrusted_mods: bool,
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+fn path() -> PathBuf {
+    PathBuf::from("Ferrex").join("Config.toml")
+}
+
+fn load() -> Config {
+    let content = match fs::read_to_string(path()) {
+        Ok(content) => content,
+        Err(_) => return Config::default(),
+    };
+
+    toml::from_str(&content).unwrap_or_else(|e| {
+        let _ = warn!("Failed to parse {}, using defaults: {}", path().display(), e.to_string());
+        Config::default()
+    })
+}
+
+/// the config, read once on first use
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(load)
+}
@@ -0,0 +1,98 @@
+//! commands typed into the console while the game runs
+//!
+//! once the mods are started, a thread owns stdin and hands every line over to the game
+//! thread, which runs them once a frame. prompts read their answers through [`read_line`]
+//! as well, so the two never fight over a line.
+
+use std::{
+    io::{self, BufRead},
+    sync::{
+        mpsc::{self, Receiver},
+        Mutex, OnceLock,
+    },
+    thread,
+};
+
+use crate::{err, hooking::registry, log, warn};
+
+/// the lines read since the listener started
+static LINES: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
+
+const COMMANDS: [(&str, &str); 2] = [
+    ("hooks", "lists every installed hook and patch"),
+    ("help", "lists the commands"),
+];
+
+/// starts reading commands from the console, only the first call does anything
+pub fn listen() {
+    LINES.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+
+        // ends with stdin, which is often closed when the game isn't started from a terminal
+        let spawned = thread::Builder::new().name("ferrex-console".to_string()).spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        if let Err(e) = spawned {
+            let _ = err!("Failed to listen for console commands: {}", e.to_string());
+        }
+
+        Mutex::new(receiver)
+    });
+}
+
+/// the next line typed into the console, none once stdin is closed
+pub fn read_line() -> Option<String> {
+    if let Some(lines) = LINES.get() {
+        return lines.lock().unwrap_or_else(|e| e.into_inner()).recv().ok();
+    }
+
+    let mut line = String::new();
+
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line),
+    }
+}
+
+fn run(command: &str) {
+    match command {
+        "" => {}
+        "hooks" => {
+            if registry::table().is_empty() {
+                let _ = log!("No hooks installed");
+            }
+
+            registry::log_table();
+        }
+        "help" => {
+            for (name, description) in COMMANDS {
+                let _ = log!("  {}: {}", name, description);
+            }
+        }
+        command => {
+            let _ = warn!("Unknown command {}, type help for a list", command);
+        }
+    }
+}
+
+/// runs the commands typed since the last call, called once a frame
+pub fn run_pending() {
+    let Some(lines) = LINES.get() else {
+        return;
+    };
+
+    let pending: Vec<String> = lines.lock().unwrap_or_else(|e| e.into_inner()).try_iter().collect();
+
+    for line in pending {
+        run(line.trim());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod commands;
 pub mod os;
 
 #[cfg(all(unix))]
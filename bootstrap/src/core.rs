@@ -3,7 +3,7 @@ use std::{error::Error};
 
 use unity_rs::runtime::{Runtime, self, FerrexRuntime};
 
-use crate::{hooking, log, logging::logger, errors::DynErr, console};
+use crate::{game, hooking, log, err, logging::logger, errors::DynErr, console, mods};
 
 pub fn init() -> Result<(), Box<dyn Error>> {
     console::init()?;
@@ -15,9 +15,16 @@ pub fn init() -> Result<(), Box<dyn Error>> {
 
 
     log!("Initializing Ferrex")?;
+    log!("Game: {}", game::get())?;
 
     //hooking::init::hook_init()?;
     hooking::invoke::hook_invoke()?;
+
+    // a broken plugin shouldn't keep the mods from loading later
+    if let Err(e) = mods::manager::load_plugins() {
+        err!("Failed to load plugins: {}", e.to_string())?;
+    }
+
     console::null_handles()?;
 
     Ok(())
@@ -23,4 +23,18 @@ pub enum HookError {
     Nullpointer(String),
     #[error("Trampoline to {0} is none!")]
     NoTrampoline(String),
+    #[error("Hook on {0} is not owned by the caller")]
+    NotOwned(String),
+    #[error("Method {0} not found")]
+    MethodNotFound(String),
+    #[error("Method {0} is overloaded, its parameter types are needed to tell which one")]
+    AmbiguousMethod(String),
+    #[error("{0} is already hooked by {1}")]
+    Conflict(String, String),
+    #[error("All {0} patchable targets are in use")]
+    NoFreeSlot(usize),
+    #[error("Method {0} can't be hooked, {1}")]
+    NoNativeCode(String, &'static str),
+    #[error("{0} can't be patched, {1}")]
+    Unpatchable(String, String),
 }
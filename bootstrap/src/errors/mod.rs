@@ -1,5 +1,6 @@
 pub mod hookerr;
 pub mod moderr;
 pub mod conerr;
+pub mod preferr;
 
 pub type DynErr = Box<dyn std::error::Error>;
\ No newline at end of file
@@ -15,4 +15,29 @@ pub enum ModError {
     
     #[error(transparent)]
     Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Library(#[from] libloading::Error),
+
+    #[error("Invalid manifest {0}: {1}")]
+    InvalidManifest(String, String),
+
+    #[error("{0} is not trusted: {1}")]
+    Untrusted(String, String),
+
+    #[error("{0} does not export ferrex_mod_info")]
+    MissingModInfo(String),
+
+    #[error("{0} has no class marked with [FerrexMod], and no {1} class")]
+    MissingEntryType(String, String),
+
+    #[error("{0} is a managed mod, which can't be loaded on {1}")]
+    UnsupportedRuntime(String, String),
+
+    #[error("{name} was built against abi version {found}, but Ferrex expects {expected}")]
+    AbiMismatch {
+        name: String,
+        found: u32,
+        expected: u32,
+    },
 }
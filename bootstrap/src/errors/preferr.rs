@@ -0,0 +1,30 @@
+use std::io;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PreferenceError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Parse(#[from] toml::de::Error),
+
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+
+    #[error("{0} can't be used as a file name in Ferrex/UserData")]
+    InvalidId(String),
+
+    #[error("No preferences are registered for this mod")]
+    UnknownOwner,
+
+    #[error("{0} has no entry {1}.{2}")]
+    UnknownEntry(String, String, String),
+
+    #[error("Invalid value for {0}.{1}: {2}")]
+    InvalidValue(String, String, String),
+
+    #[error("Default of {0}.{1} is invalid: {2}")]
+    InvalidDefault(String, String, String),
+}
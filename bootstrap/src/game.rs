@@ -0,0 +1,156 @@
+//! which game Ferrex is running in, read from the files in `<Game>_Data`
+//!
+//! `app.info` holds the company and product name on two lines. the unity version is read
+//! from the header of `globalgamemanagers`, or `data.unity3d` and `mainData` in games that
+//! don't have one.
+
+use std::{
+    env,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use unity_rs::utils::path::get_data_path;
+
+use crate::{errors::DynErr, warn};
+
+#[derive(Debug, Clone, Default)]
+pub struct GameInfo {
+    pub company: String,
+    pub product: String,
+    /// like `2019.4.31f1`, empty if it couldn't be read
+    pub unity_version: String,
+}
+
+impl GameInfo {
+    /// whether the game could be identified at all
+    pub fn is_known(&self) -> bool {
+        !self.product.is_empty()
+    }
+}
+
+impl std::fmt::Display for GameInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} by {}", self.product, self.company)?;
+
+        if !self.unity_version.is_empty() {
+            write!(f, " (Unity {})", self.unity_version)?;
+        }
+
+        Ok(())
+    }
+}
+
+static GAME: OnceLock<GameInfo> = OnceLock::new();
+
+fn data_path() -> Result<PathBuf, DynErr> {
+    get_data_path(&env::current_exe()?)
+}
+
+fn read_app_info(data_path: &Path) -> Result<(String, String), DynErr> {
+    let content = std::fs::read_to_string(data_path.join("app.info"))?;
+    let mut lines = content.lines().map(str::trim);
+
+    let company = lines.next().unwrap_or_default().to_string();
+    let product = lines.next().unwrap_or_default().to_string();
+
+    Ok((company, product))
+}
+
+/// the first bytes of a file, the headers we read are well within them
+fn read_header(path: &Path) -> Option<Vec<u8>> {
+    let mut header = Vec::new();
+
+    File::open(path).ok()?.take(512).read_to_end(&mut header).ok()?;
+
+    Some(header)
+}
+
+/// a null terminated string at `offset`, if it looks like a unity version
+fn read_version(data: &[u8], offset: usize) -> Option<String> {
+    let data = data.get(offset..)?;
+    let end = data.iter().position(|b| *b == 0)?;
+    let version = std::str::from_utf8(&data[..end]).ok()?;
+
+    let valid = version.len() < 32
+        && version.starts_with(|c: char| c.is_ascii_digit())
+        && version.chars().all(|c| c.is_ascii_alphanumeric() || c == '.');
+
+    valid.then(|| version.to_string())
+}
+
+/// the version a serialized file like `globalgamemanagers` was written by
+fn serialized_file_version(data: &[u8]) -> Option<String> {
+    let format = u32::from_be_bytes(data.get(8..12)?.try_into().ok()?);
+    let mut offset = 16;
+
+    // the endianness and 3 reserved bytes
+    if format >= 9 {
+        offset += 4;
+    }
+
+    // the header was extended with 64 bit sizes and offsets
+    if format >= 22 {
+        offset += 28;
+    }
+
+    read_version(data, offset)
+}
+
+/// the engine version in the header of a `UnityFS` bundle, after the format version and
+/// the bundle version string
+fn bundle_version(data: &[u8]) -> Option<String> {
+    let data = data.strip_prefix(b"UnityFS\0")?;
+    let data = data.get(4..)?;
+    let bundle_version_end = data.iter().position(|b| *b == 0)?;
+
+    read_version(data, bundle_version_end + 1)
+}
+
+fn read_unity_version(data_path: &Path) -> Option<String> {
+    ["globalgamemanagers", "mainData"]
+        .iter()
+        .find_map(|name| serialized_file_version(&read_header(&data_path.join(name))?))
+        .or_else(|| bundle_version(&read_header(&data_path.join("data.unity3d"))?))
+}
+
+fn load() -> GameInfo {
+    let data_path = match data_path() {
+        Ok(data_path) => data_path,
+        Err(e) => {
+            let _ = warn!("Failed to find the game's data folder: {}", e.to_string());
+            return GameInfo::default();
+        }
+    };
+
+    let (company, product) = read_app_info(&data_path).unwrap_or_else(|e| {
+        let _ = warn!("Failed to read app.info: {}", e.to_string());
+        Default::default()
+    });
+
+    let unity_version = read_unity_version(&data_path).unwrap_or_else(|| {
+        let _ = warn!("Failed to read the Unity version");
+        String::new()
+    });
+
+    GameInfo {
+        company,
+        product,
+        unity_version,
+    }
+}
+
+/// the game, read once on first use
+pub fn get() -> &'static GameInfo {
+    GAME.get_or_init(load)
+}
+
+/// whether `version` is `prefix`, or a release of it, `2019.4` matches `2019.4.31f1` but not `2019.40.1`
+pub fn version_matches(version: &str, prefix: &str) -> bool {
+    match version.strip_prefix(prefix) {
+        Some(rest) => !rest.starts_with(|c: char| c.is_ascii_digit()),
+        None => false,
+    }
+}
@@ -0,0 +1,398 @@
+//! prefixes and postfixes, so several mods can patch the same function
+//!
+//! dobby allows a single detour per address, so every patched target gets one detour, taken
+//! from a fixed pool, which runs the target's patches around the original:
+//!
+//! - prefixes run first, from the highest priority to the lowest, and may change the
+//!   arguments, or skip the original by clearing `run_original`
+//! - then the original, unless a prefix skipped it, in which case the return value is
+//!   whatever the prefixes left in `return_value`
+//! - postfixes run last in the same order, even if the original was skipped, and may replace
+//!   the return value
+//!
+//! patches with the same priority run in the order they were added. a patch that panics is
+//! disabled right away, and removed on the next frame, outside of any detour.
+//!
+//! the detours forward the first [`MAX_ARGS`] arguments and return a single register, which
+//! covers pointers, objects and integers, but not floating point arguments or return values.
+//! methods found through [`method::find`] are checked and refused if they don't fit, raw
+//! symbols are trusted to.
+
+use std::{
+    mem,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use arc_swap::ArcSwapOption;
+use chrono::{DateTime, Local};
+use dobby_rs::Address;
+use lazy_static::lazy_static;
+
+use crate::{err, errors::hookerr::HookError};
+
+use super::{method, registry};
+
+/// the amount of arguments passed through to handlers and the original
+pub const MAX_ARGS: usize = 8;
+/// how many targets can be patched at once
+const SLOTS: usize = 64;
+/// the owner of the hooks Ferrex installs itself, like the dispatcher's detours
+pub const FERREX_OWNER: u64 = 0;
+
+type Detour = extern "C" fn(usize, usize, usize, usize, usize, usize, usize, usize) -> usize;
+
+/// a call to a patched function, as seen by its patches
+#[derive(Debug)]
+#[repr(C)]
+pub struct HookCall {
+    /// the patched function
+    pub target: *mut std::ffi::c_void,
+    /// passed on to the original, so prefixes may change them
+    pub args: [usize; MAX_ARGS],
+    /// set once the original ran, postfixes may replace it
+    pub return_value: usize,
+    /// prefixes clear this to skip the original
+    pub run_original: bool,
+}
+
+pub type Handler = Arc<dyn Fn(&mut HookCall) + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum PatchKind {
+    Prefix = 0,
+    Postfix = 1,
+}
+
+impl TryFrom<u32> for PatchKind {
+    type Error = String;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PatchKind::Prefix),
+            1 => Ok(PatchKind::Postfix),
+            _ => Err(format!("Unknown patch kind {}", value)),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Patch {
+    id: u64,
+    owner: u64,
+    priority: i32,
+    handler: Handler,
+    /// the function behind the handler, for the hook table
+    function: usize,
+    installed: DateTime<Local>,
+    /// set once the handler panicked, shared by every copy of the chain
+    disabled: Arc<AtomicBool>,
+}
+
+/// a patch, as listed in the hook table
+#[derive(Debug, Clone)]
+pub struct PatchInfo {
+    pub id: u64,
+    pub target: usize,
+    pub owner: u64,
+    pub kind: PatchKind,
+    pub priority: i32,
+    pub handler: usize,
+    pub installed: DateTime<Local>,
+}
+
+/// the patches on a target, replaced as a whole whenever they change, so calls in flight
+/// keep the chain they started with
+#[derive(Clone)]
+struct Chain {
+    target: usize,
+    prefixes: Vec<Patch>,
+    postfixes: Vec<Patch>,
+}
+
+impl Chain {
+    fn patches(&mut self, kind: PatchKind) -> &mut Vec<Patch> {
+        match kind {
+            PatchKind::Prefix => &mut self.prefixes,
+            PatchKind::Postfix => &mut self.postfixes,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.prefixes.is_empty() && self.postfixes.is_empty()
+    }
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// the original of each slot's target. kept apart from the chains, and only cleared once the
+/// detour is detached, so a call that's already in the detour can always reach the original
+static TRAMPOLINES: [AtomicUsize; SLOTS] = [const { AtomicUsize::new(0) }; SLOTS];
+
+/// set when a patch panicked, so [`remove_disabled`] has work to do
+static HAS_DISABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    /// read by the detours without locking
+    static ref CHAINS: Vec<ArcSwapOption<Chain>> = (0..SLOTS).map(|_| ArcSwapOption::empty()).collect();
+    /// held while changing the chains, so changes don't overwrite each other
+    static ref WRITER: Mutex<()> = Mutex::new(());
+}
+
+/// a detour per slot, each one knows which chain it belongs to
+macro_rules! detours {
+    ($($slot:literal)*) => {
+        [$({
+            extern "C" fn detour(a0: usize, a1: usize, a2: usize, a3: usize, a4: usize, a5: usize, a6: usize, a7: usize) -> usize {
+                dispatch($slot, [a0, a1, a2, a3, a4, a5, a6, a7])
+            }
+
+            detour as Detour
+        },)*]
+    };
+}
+
+static DETOURS: [Detour; SLOTS] = detours!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+    32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+);
+
+/// whether `detour` is one of the dispatcher's detours
+pub fn is_detour(detour: usize) -> bool {
+    DETOURS.iter().any(|d| *d as usize == detour)
+}
+
+/// runs a handler, a panic must not unwind into the game, and disables the patch
+///
+/// removing it here could detach the detour the call is still in, so that's left to
+/// [`remove_disabled`]
+fn run(patch: &Patch, call: &mut HookCall) {
+    if patch.disabled.load(Ordering::Acquire) {
+        return;
+    }
+
+    let panicked = panic::catch_unwind(AssertUnwindSafe(|| (patch.handler)(call))).is_err();
+
+    // another thread may have disabled it at the same time
+    if !panicked || patch.disabled.swap(true, Ordering::AcqRel) {
+        return;
+    }
+
+    HAS_DISABLED.store(true, Ordering::Release);
+
+    let _ = err!(
+        "Patch {} on {:p} by {} panicked, disabling it",
+        patch.id,
+        call.target,
+        registry::owner_name(patch.owner)
+    );
+}
+
+/// the original of `slot`'s target. [`add`] stores it once dobby returns it, but the detour
+/// may already be running by then, so wait for it while the slot is in use
+fn trampoline(slot: usize) -> usize {
+    loop {
+        let trampoline = TRAMPOLINES[slot].load(Ordering::Acquire);
+
+        if trampoline != 0 || CHAINS[slot].load().is_none() {
+            return trampoline;
+        }
+
+        std::hint::spin_loop();
+    }
+}
+
+fn dispatch(slot: usize, args: [usize; MAX_ARGS]) -> usize {
+    let chain = CHAINS[slot].load_full();
+    let trampoline = trampoline(slot);
+
+    let mut call = HookCall {
+        target: chain.as_ref().map_or(std::ptr::null_mut(), |chain| chain.target as *mut std::ffi::c_void),
+        args,
+        return_value: 0,
+        run_original: true,
+    };
+
+    // the chain is gone while the detour is being removed, then only the original runs
+    if let Some(chain) = chain.as_deref() {
+        for prefix in chain.prefixes.iter() {
+            run(prefix, &mut call);
+        }
+    }
+
+    if call.run_original && trampoline != 0 {
+        let original: Detour = unsafe { mem::transmute(trampoline) };
+        let [a0, a1, a2, a3, a4, a5, a6, a7] = call.args;
+
+        call.return_value = original(a0, a1, a2, a3, a4, a5, a6, a7);
+    }
+
+    if let Some(chain) = chain.as_deref() {
+        for postfix in chain.postfixes.iter() {
+            run(postfix, &mut call);
+        }
+    }
+
+    call.return_value
+}
+
+/// adds a patch to `target` on behalf of `owner`, returns its id. `function` is whatever
+/// `handler` calls, and only shown in the hook table
+pub fn add(
+    owner: u64,
+    target: Address,
+    kind: PatchKind,
+    priority: i32,
+    handler: Handler,
+    function: Address,
+) -> Result<u64, HookError> {
+    if target.is_null() {
+        return Err(HookError::Nullpointer("target".to_string()));
+    }
+
+    if let Some(reason) = method::unpatchable(target) {
+        return Err(HookError::Unpatchable(format!("{:p}", target), reason));
+    }
+
+    let _writer = WRITER.lock().unwrap_or_else(|e| e.into_inner());
+    let existing = CHAINS
+        .iter()
+        .position(|chain| chain.load().as_ref().is_some_and(|c| c.target == target as usize));
+
+    let slot = match existing {
+        Some(slot) => slot,
+        None => {
+            let slot = CHAINS
+                .iter()
+                .position(|chain| chain.load().is_none())
+                .ok_or(HookError::NoFreeSlot(SLOTS))?;
+
+            // the chain goes in first, so the detour never runs without it
+            CHAINS[slot].store(Some(Arc::new(Chain {
+                target: target as usize,
+                prefixes: Vec::new(),
+                postfixes: Vec::new(),
+            })));
+
+            match registry::attach(FERREX_OWNER, target, DETOURS[slot] as Address) {
+                Ok(trampoline) => TRAMPOLINES[slot].store(trampoline as usize, Ordering::Release),
+                Err(e) => {
+                    CHAINS[slot].store(None);
+                    return Err(e);
+                }
+            }
+
+            slot
+        }
+    };
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let mut chain = CHAINS[slot].load().as_deref().cloned().ok_or(HookError::Null)?;
+    let patches = chain.patches(kind);
+
+    // after every patch with the same or a higher priority
+    let index = patches.partition_point(|patch| patch.priority >= priority);
+    patches.insert(
+        index,
+        Patch {
+            id,
+            owner,
+            priority,
+            handler,
+            function: function as usize,
+            installed: Local::now(),
+            disabled: Arc::new(AtomicBool::new(false)),
+        },
+    );
+
+    CHAINS[slot].store(Some(Arc::new(chain)));
+
+    Ok(id)
+}
+
+/// removes the patches matching `f` from every chain, and the detours left without any
+fn remove_where(f: impl Fn(&Patch) -> bool) -> usize {
+    let _writer = WRITER.lock().unwrap_or_else(|e| e.into_inner());
+    let mut removed = 0;
+
+    for (index, slot) in CHAINS.iter().enumerate() {
+        let Some(chain) = slot.load_full() else {
+            continue;
+        };
+
+        let mut chain = Chain::clone(&chain);
+        let count = chain.prefixes.len() + chain.postfixes.len();
+
+        chain.prefixes.retain(|patch| !f(patch));
+        chain.postfixes.retain(|patch| !f(patch));
+
+        if chain.prefixes.len() + chain.postfixes.len() == count {
+            continue;
+        }
+
+        removed += count - chain.prefixes.len() - chain.postfixes.len();
+
+        if !chain.is_empty() {
+            slot.store(Some(Arc::new(chain)));
+            continue;
+        }
+
+        if let Err(e) = registry::detach(FERREX_OWNER, chain.target as Address) {
+            let _ = err!("Failed to remove the detour on {:#x}: {}", chain.target, e.to_string());
+        }
+
+        slot.store(None);
+        TRAMPOLINES[index].store(0, Ordering::Release);
+    }
+
+    removed
+}
+
+/// removes a patch, as long as `owner` is the one that added it
+pub fn remove(owner: u64, id: u64) -> Result<(), HookError> {
+    match remove_where(|patch| patch.id == id && patch.owner == owner) {
+        0 => Err(HookError::NotOwned(format!("patch {}", id))),
+        _ => Ok(()),
+    }
+}
+
+/// removes every patch `owner` added
+pub fn remove_owner(owner: u64) -> usize {
+    remove_where(|patch| patch.owner == owner)
+}
+
+/// removes the patches that panicked, called once a frame, where no detour is running
+pub fn remove_disabled() -> usize {
+    if !HAS_DISABLED.swap(false, Ordering::AcqRel) {
+        return 0;
+    }
+
+    remove_where(|patch| patch.disabled.load(Ordering::Acquire))
+}
+
+/// every patch, in the order they run
+pub fn patches() -> Vec<PatchInfo> {
+    let mut patches = Vec::new();
+
+    for chain in CHAINS.iter().filter_map(ArcSwapOption::load_full) {
+        let kinds = [(PatchKind::Prefix, &chain.prefixes), (PatchKind::Postfix, &chain.postfixes)];
+
+        for (kind, list) in kinds {
+            patches.extend(list.iter().map(|patch| PatchInfo {
+                id: patch.id,
+                target: chain.target,
+                owner: patch.owner,
+                kind,
+                priority: patch.priority,
+                handler: patch.function,
+                installed: patch.installed,
+            }));
+        }
+    }
+
+    patches
+}
@@ -1,20 +1,34 @@
-use std::{error::Error, mem::transmute};
+use std::{
+    error::Error,
+    mem::transmute,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+};
 
 use dobby_rs::Address;
 use unity_rs::{
-    common::method::UnityMethod,
+    common::{class::UnityClass, method::UnityMethod},
     runtime::{Runtime, RuntimeType},
 };
 
 
-use crate::{errors::{hookerr::HookError, DynErr}, internal_failure, log, mods::manager::ModManager, core, console};
+use crate::{errors::{hookerr::HookError, DynErr}, internal_failure, log, err, warn, mods::{manager, scenes}, core, console};
 
-use super::hook;
+use super::{dispatcher::FERREX_OWNER, player_loop, registry};
 
 type InvokeFn = fn(Address, Address, *mut Address, *mut Address) -> Address;
 
 static mut INVOKE_ORIGINAL: Option<InvokeFn> = None;
 
+/// set once the mods are started, after which the hook only looks for scene events and quitting
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// `Application.Internal_ApplicationQuit`, which unity calls once it's really quitting.
+/// none if this unity version doesn't have it
+static QUIT_METHOD: OnceLock<Option<usize>> = OnceLock::new();
+
 pub fn hook_invoke() -> Result<(), HookError> {
     let runtime = unity_rs::runtime::get_runtime()?;
 
@@ -34,7 +48,8 @@ pub fn hook_invoke() -> Result<(), HookError> {
     log!("Attaching Hook to runtime_invoke")?;
 
     unsafe {
-        INVOKE_ORIGINAL = Some(transmute(hook::attach(
+        INVOKE_ORIGINAL = Some(transmute(registry::attach(
+            FERREX_OWNER,
             runtime_invoke,
             invoke_detour as Address,
         )?));
@@ -68,6 +83,34 @@ fn invoke_detour_inner(
         original(method, object, params, exception)
     };
 
+    // only the thread that flips it starts the mods
+    if !STARTED.load(Ordering::Acquire)
+        && should_start(method)?
+        && STARTED.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).is_ok()
+    {
+        start();
+    }
+
+    // the scene callbacks only run after unity has handled the change itself. a scene that
+    // can't be read isn't worth taking the game down for
+    match scenes::event(method.cast(), params.cast()) {
+        Ok(Some(event)) => manager::scene_event(&event),
+        Ok(None) => {}
+        Err(e) => {
+            let _ = err!("Failed to read a scene event: {}", e.to_string());
+        }
+    }
+
+    // the quitting handlers ran, but the runtime is still around for the mods' on_quit
+    if QUIT_METHOD.get().copied().flatten() == Some(method as usize) {
+        manager::quit();
+    }
+
+    Ok(ret)
+}
+
+/// whether `method` is a sign that the game is far enough along to start the mods
+fn should_start(method: Address) -> Result<bool, DynErr> {
     let runtime = core::get_runtime()?;
 
     let unity_method = UnityMethod {
@@ -81,24 +124,37 @@ fn invoke_detour_inner(
         RuntimeType::Il2Cpp(_) => false,
     };
 
-    let should_run = (name.contains("Internal_ActiveSceneChanged")
+    Ok((name.contains("Internal_ActiveSceneChanged")
         || name.contains("UnityEngine.ISerializationCallbackReceiver.OnAfterSerialize"))
-        || (is_old_mono && (name.contains("Awake") || name.contains("DoSendMouseEvents")));
+        || (is_old_mono && (name.contains("Awake") || name.contains("DoSendMouseEvents"))))
+}
 
-    if !should_run {
-        return Ok(ret);
-    }
+fn find_quit_method() -> Result<usize, DynErr> {
+    let runtime = core::get_runtime()?;
 
-    unsafe {
-        let runtime_invoke = match runtime.get_type() {
-            RuntimeType::Mono(mono) => mono.get_export_ptr("mono_runtime_invoke")?,
-            RuntimeType::Il2Cpp(il2cpp) => il2cpp.get_export_ptr("il2cpp_runtime_invoke")?,
-        };
+    let application = UnityClass::find(None, "UnityEngine", "Application", runtime)?;
 
-        hook::detach(transmute(runtime_invoke))?;
+    Ok(application.get_method("Internal_ApplicationQuit", 0, runtime)?.inner as usize)
+}
 
-        let _ = ModManager::new()?;
+/// the hook stays attached afterwards, it's what scene events and quitting are read from
+fn start() {
+    // a broken mod setup shouldn't take the game down with it
+    if let Err(e) = manager::start() {
+        let _ = err!("Failed to start mods: {}", e.to_string());
     }
 
-    Ok(ret)
+    if let Err(e) = player_loop::inject() {
+        let _ = err!("Failed to add frame callbacks: {}", e.to_string());
+    }
+
+    scenes::init();
+
+    QUIT_METHOD.get_or_init(|| match find_quit_method() {
+        Ok(method) => Some(method),
+        Err(e) => {
+            let _ = warn!("on_quit is unavailable: {}", e.to_string());
+            None
+        }
+    });
 }
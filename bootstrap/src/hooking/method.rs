@@ -0,0 +1,205 @@
+//! hooks on managed methods, found by assembly, class, name and parameter types
+//!
+//! the detour replaces the method's native code, so it's called the way the runtime calls
+//! compiled code: instance methods take `this` first, and il2cpp passes the `MethodInfo*`
+//! as an extra last argument. the returned trampoline takes the same arguments.
+//! on mono the method is jit compiled first, so it doesn't matter whether it has run yet.
+//!
+//! [`find`] also notes which methods the dispatcher can't patch, see [`unpatchable`].
+
+use std::{collections::HashMap, fmt, sync::Mutex};
+
+use dobby_rs::Address;
+use lazy_static::lazy_static;
+use unity_rs::{
+    common::{class::UnityClass, method::UnityMethod, ty::UnityType},
+    runtime::{FerrexRuntime, RuntimeType},
+};
+
+use crate::{core, errors::{hookerr::HookError, DynErr}};
+
+use super::{dispatcher::MAX_ARGS, registry};
+
+/// the value types that fit in an integer register, anything else is passed in vector
+/// registers or spread across several
+const INTEGER_TYPES: &[&str] = &[
+    "System.Boolean",
+    "System.Char",
+    "System.SByte",
+    "System.Byte",
+    "System.Int16",
+    "System.UInt16",
+    "System.Int32",
+    "System.UInt32",
+    "System.Int64",
+    "System.UInt64",
+    "System.IntPtr",
+    "System.UIntPtr",
+];
+
+lazy_static! {
+    /// why the dispatcher can't patch a method [`find`] resolved, by native code
+    static ref UNPATCHABLE: Mutex<HashMap<usize, String>> = Mutex::new(HashMap::new());
+}
+
+/// a managed method, like `Assembly-CSharp` `Game.Player::Jump(System.Single)`
+#[derive(Debug, Clone)]
+pub struct MethodTarget {
+    /// the assembly name, without `.dll`
+    pub assembly: String,
+    pub namespace: String,
+    pub class: String,
+    pub method: String,
+    /// the full names of the parameter types, like `System.Int32`. if none, the method's name
+    /// has to be unique in its class
+    pub params: Option<Vec<String>>,
+}
+
+impl fmt::Display for MethodTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.namespace.is_empty() {
+            true => write!(f, "{}::{}", self.class, self.method)?,
+            false => write!(f, "{}.{}::{}", self.namespace, self.class, self.method)?,
+        }
+
+        match &self.params {
+            Some(params) => write!(f, "({})", params.join(", ")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl MethodTarget {
+    fn params_match(&self, method: &UnityMethod, params: &[String], runtime: &FerrexRuntime) -> Result<bool, HookError> {
+        let types = method.get_param_types(runtime)?;
+
+        if types.len() != params.len() {
+            return Ok(false);
+        }
+
+        for (ty, param) in types.iter().zip(params) {
+            if ty.get_name(runtime)? != *param {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// looks the method up, overloads are told apart by their parameter types
+    pub fn resolve(&self, runtime: &FerrexRuntime) -> Result<UnityMethod, HookError> {
+        let class = UnityClass::find(Some(&self.assembly), &self.namespace, &self.class, runtime)?;
+        let mut found = Vec::new();
+
+        for method in class.get_methods(runtime)? {
+            if method.get_name(runtime)? != self.method {
+                continue;
+            }
+
+            let matches = match &self.params {
+                Some(params) => self.params_match(&method, params, runtime)?,
+                None => true,
+            };
+
+            if matches {
+                found.push(method);
+            }
+        }
+
+        match found.len() {
+            0 => Err(HookError::MethodNotFound(self.to_string())),
+            1 => Ok(found[0]),
+            _ => Err(HookError::AmbiguousMethod(self.to_string())),
+        }
+    }
+}
+
+/// the native code of `method`, which is what gets hooked
+pub fn native_code(method: &UnityMethod, runtime: &FerrexRuntime) -> Result<Address, HookError> {
+    let signature = method.get_signature(runtime)?;
+
+    if signature.is_abstract {
+        return Err(HookError::NoNativeCode(signature.name, "it is abstract"));
+    }
+
+    // a generic definition has no code of its own, only its instantiations do
+    if signature.generic_arity > 0 && !method.is_inflated(runtime)? {
+        return Err(HookError::NoNativeCode(signature.name, "it is generic"));
+    }
+
+    Ok(method.compile(runtime)?)
+}
+
+/// whether `ty` is passed in a single integer register
+fn fits_register(ty: &UnityType, runtime: &FerrexRuntime) -> Result<bool, HookError> {
+    if ty.is_byref(runtime)? {
+        return Ok(true);
+    }
+
+    let class = ty.get_class(runtime)?;
+
+    if !class.is_valuetype(runtime)? || class.is_enum(runtime)? {
+        return Ok(true);
+    }
+
+    Ok(INTEGER_TYPES.contains(&ty.get_name(runtime)?.as_str()))
+}
+
+/// why the dispatcher's detours can't forward calls to `method`, if they can't. they pass
+/// on [`MAX_ARGS`] integer registers and return one
+pub fn check_patchable(method: &UnityMethod, runtime: &FerrexRuntime) -> Result<Option<String>, HookError> {
+    let signature = method.get_signature(runtime)?;
+
+    let extra = match runtime.get_type() {
+        RuntimeType::Il2Cpp(_) => 1,
+        RuntimeType::Mono(_) => 0,
+    };
+    let args = signature.parameters.len() + usize::from(!signature.is_static) + extra;
+
+    if args > MAX_ARGS {
+        return Ok(Some(format!("it takes {} arguments, patches support up to {}", args, MAX_ARGS)));
+    }
+
+    for param in signature.parameters.iter() {
+        if !fits_register(&param.ty, runtime)? {
+            return Ok(Some(format!("it takes a {}, patches only support integers and references", param.ty.get_name(runtime)?)));
+        }
+    }
+
+    let ret = signature.return_type;
+    let name = ret.get_name(runtime)?;
+
+    if name != "System.Void" && !fits_register(&ret, runtime)? {
+        return Ok(Some(format!("it returns a {}, patches only support integers and references", name)));
+    }
+
+    Ok(None)
+}
+
+/// why the dispatcher can't patch `target`, if it's a method [`find`] resolved. symbols
+/// aren't checked, they have to take and return integers and pointers only
+pub fn unpatchable(target: Address) -> Option<String> {
+    UNPATCHABLE.lock().unwrap_or_else(|e| e.into_inner()).get(&(target as usize)).cloned()
+}
+
+/// resolves `target` to its native code
+pub fn find(target: &MethodTarget) -> Result<Address, DynErr> {
+    let runtime = core::get_runtime()?;
+    let method = target.resolve(runtime)?;
+    let code = native_code(&method, runtime)?;
+
+    let mut unpatchable = UNPATCHABLE.lock().unwrap_or_else(|e| e.into_inner());
+    match check_patchable(&method, runtime)? {
+        Some(reason) => unpatchable.insert(code as usize, reason),
+        None => unpatchable.remove(&(code as usize)),
+    };
+
+    Ok(code)
+}
+
+/// hooks `target` on behalf of `owner`, returning the trampoline to the original
+///
+/// detach it through [`registry::detach`], with the address [`find`] returns
+pub fn attach(owner: u64, target: &MethodTarget, detour: Address) -> Result<Address, DynErr> {
+    Ok(registry::attach(owner, find(target)?, detour)?)
+}
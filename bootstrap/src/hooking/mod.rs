@@ -1,2 +1,7 @@
+pub mod dispatcher;
 pub mod hook;
-pub mod invoke;
\ No newline at end of file
+pub mod invoke;
+pub mod method;
+pub mod player_loop;
+pub mod registry;
+pub mod symbol;
\ No newline at end of file
@@ -0,0 +1,265 @@
+//! per-frame callbacks, through native systems added to unity's player loop
+//!
+//! a `PlayerLoopSystem` can point at a native update function, which unity calls wherever the
+//! system sits in the loop. one is appended to each of the FixedUpdate, Update and PreLateUpdate
+//! phases, so mods tick right after the scripts' FixedUpdate, Update and LateUpdate.
+//! this works the same on mono and il2cpp, but needs unity 2018.1 or newer.
+//!
+//! the loop is only edited through its managed api, so the gc sees every write.
+//!
+//! there's no OnGUI callback. unity only runs imgui for behaviours that define OnGUI, and
+//! Ferrex can't define classes at runtime, so drawing with imgui is left to managed mods,
+//! which can add a MonoBehaviour of their own.
+
+use std::{
+    ffi::c_void,
+    panic,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use unity_rs::{
+    common::{class::UnityClass, field::UnityField, method::UnityMethod, object::UnityObject, string::UnityString},
+    runtime::FerrexRuntime,
+};
+
+use crate::{core, err, errors::DynErr, log, mods::manager, warn};
+
+/// `UnityEngine.LowLevel` since 2019.3, experimental before that
+const NAMESPACES: [&str; 2] = ["UnityEngine.LowLevel", "UnityEngine.Experimental.LowLevel"];
+
+/// unity calls through a pointer to the update function, so they need a fixed address
+static FIXED_UPDATE: extern "C" fn() = fixed_update;
+static UPDATE: extern "C" fn() = update;
+static LATE_UPDATE: extern "C" fn() = late_update;
+
+static INJECTED: AtomicBool = AtomicBool::new(false);
+
+/// the phase a system is appended to, and the function it calls
+const PHASES: [(&str, &extern "C" fn()); 3] = [
+    ("FixedUpdate", &FIXED_UPDATE),
+    ("Update", &UPDATE),
+    ("PreLateUpdate", &LATE_UPDATE),
+];
+
+/// unwinding into unity would abort the game, mods are isolated further down already
+fn tick(event: &str, f: fn()) {
+    if panic::catch_unwind(f).is_err() {
+        let _ = err!("Dispatching {} panicked", event);
+    }
+}
+
+extern "C" fn fixed_update() {
+    tick("on_fixed_update", manager::fixed_update);
+}
+
+extern "C" fn update() {
+    tick("on_update", manager::update);
+}
+
+extern "C" fn late_update() {
+    tick("on_late_update", manager::late_update);
+}
+
+/// picks an overload by the full names of its parameter types
+fn overload(class: &UnityClass, name: &str, params: &[&str], runtime: &FerrexRuntime) -> Result<UnityMethod, DynErr> {
+    for method in class.get_methods(runtime)? {
+        if method.get_name(runtime)? != name {
+            continue;
+        }
+
+        let types = method.get_param_types(runtime)?;
+
+        if types.len() == params.len()
+            && types
+                .iter()
+                .zip(params)
+                .all(|(ty, param)| ty.get_name(runtime).is_ok_and(|n| n == *param))
+        {
+            return Ok(method);
+        }
+    }
+
+    Err(format!("{}({}) not found", name, params.join(", ")).into())
+}
+
+fn field(class: &UnityClass, name: &str, runtime: &FerrexRuntime) -> Result<UnityField, DynErr> {
+    Ok(class.get_field(name, runtime)?)
+}
+
+fn int_param(value: &mut i32) -> *mut c_void {
+    value as *mut i32 as *mut c_void
+}
+
+/// what's needed to edit a loop, `PlayerLoopSystem` is a struct, so systems are boxed copies
+struct PlayerLoop<'a> {
+    runtime: &'a FerrexRuntime,
+    system: UnityClass,
+    /// `typeof(PlayerLoopSystem)`, the type of the systems we add
+    system_type: UnityObject,
+    type_field: UnityField,
+    sub_systems: UnityField,
+    update_function: UnityField,
+    get_value: UnityMethod,
+    set_value: UnityMethod,
+    copy: UnityMethod,
+    create_instance: UnityMethod,
+}
+
+impl<'a> PlayerLoop<'a> {
+    fn new(system: UnityClass, runtime: &'a FerrexRuntime) -> Result<Self, DynErr> {
+        let object = UnityClass::find(None, "System", "Object", runtime)?;
+        let array = UnityClass::find(None, "System", "Array", runtime)?;
+
+        let system_type = object
+            .get_method("GetType", 0, runtime)?
+            .try_invoke(Some(&system.new_object(runtime)?), None, runtime)?
+            .ok_or("GetType returned null")?;
+
+        Ok(PlayerLoop {
+            runtime,
+            system,
+            system_type,
+            type_field: field(&system, "type", runtime)?,
+            sub_systems: field(&system, "subSystemList", runtime)?,
+            update_function: field(&system, "updateFunction", runtime)?,
+            get_value: overload(&array, "GetValue", &["System.Int32"], runtime)?,
+            set_value: overload(&array, "SetValue", &["System.Object", "System.Int32"], runtime)?,
+            copy: overload(&array, "Copy", &["System.Array", "System.Array", "System.Int32"], runtime)?,
+            create_instance: overload(&array, "CreateInstance", &["System.Type", "System.Int32"], runtime)?,
+        })
+    }
+
+    fn sub_systems(&self, system: &UnityObject) -> Result<Option<UnityObject>, DynErr> {
+        let array: *mut c_void = self.sub_systems.get_value(system, self.runtime)?;
+
+        Ok((!array.is_null()).then_some(UnityObject { inner: array }))
+    }
+
+    fn get(&self, array: &UnityObject, index: usize) -> Result<UnityObject, DynErr> {
+        let mut index = index as i32;
+        let mut params = vec![int_param(&mut index)];
+
+        Ok(self
+            .get_value
+            .try_invoke(Some(array), Some(&mut params), self.runtime)?
+            .ok_or("GetValue returned null")?)
+    }
+
+    fn set(&self, array: &UnityObject, index: usize, system: &UnityObject) -> Result<(), DynErr> {
+        let mut index = index as i32;
+        let mut params = vec![system.inner, int_param(&mut index)];
+
+        self.set_value.try_invoke(Some(array), Some(&mut params), self.runtime)?;
+
+        Ok(())
+    }
+
+    /// the name of the phase a system stands for, like `Update`
+    fn name(&self, system: &UnityObject) -> Result<String, DynErr> {
+        let ty: *mut c_void = self.type_field.get_value(system, self.runtime)?;
+
+        if ty.is_null() {
+            return Ok(String::new());
+        }
+
+        let ty = UnityObject { inner: ty };
+        let property = ty.get_class(self.runtime)?.get_property("Name", self.runtime)?;
+
+        let name = self
+            .runtime
+            .get_property_get_method(&property)?
+            .try_invoke(Some(&ty), None, self.runtime)?
+            .ok_or("Type.Name returned null")?;
+
+        Ok(UnityString { inner: name.inner }.to_string(self.runtime)?)
+    }
+
+    /// appends a system calling `function` to the sub systems of `phase`
+    fn append(&self, phase: &UnityObject, function: &'static extern "C" fn()) -> Result<(), DynErr> {
+        let old = self.sub_systems(phase)?;
+
+        let length = match old.as_ref() {
+            Some(old) => self.runtime.get_array_length(old)?,
+            None => 0,
+        };
+
+        let mut new_length = length as i32 + 1;
+        let mut params = vec![self.system_type.inner, int_param(&mut new_length)];
+
+        let new = self
+            .create_instance
+            .try_invoke(None, Some(&mut params), self.runtime)?
+            .ok_or("CreateInstance returned null")?;
+
+        if let Some(old) = old {
+            let mut length = length as i32;
+            let mut params = vec![old.inner, new.inner, int_param(&mut length)];
+
+            self.copy.try_invoke(None, Some(&mut params), self.runtime)?;
+        }
+
+        let system = self.system.new_object(self.runtime)?;
+        let mut pointer = function as *const extern "C" fn() as isize;
+
+        self.type_field.set_value(&system, self.system_type.inner, self.runtime)?;
+        self.update_function
+            .set_value(&system, &mut pointer as *mut isize as *mut c_void, self.runtime)?;
+
+        self.set(&new, length, &system)?;
+        self.sub_systems.set_value(phase, new.inner, self.runtime)?;
+
+        Ok(())
+    }
+}
+
+/// adds the frame callbacks to the current player loop, only the first call does anything
+pub fn inject() -> Result<(), DynErr> {
+    if INJECTED.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    let runtime = core::get_runtime()?;
+
+    let Some((namespace, player_loop)) = NAMESPACES
+        .iter()
+        .find_map(|namespace| UnityClass::find(None, namespace, "PlayerLoop", runtime).ok().map(|class| (*namespace, class)))
+    else {
+        warn!("This game has no PlayerLoop api, mods won't get frame callbacks")?;
+        return Ok(());
+    };
+
+    let system = UnityClass::find(None, namespace, "PlayerLoopSystem", runtime)?;
+    let editor = PlayerLoop::new(system, runtime)?;
+
+    // the current loop may already have been changed by the game, which we'd like to keep
+    let root = player_loop
+        .get_method("GetCurrentPlayerLoop", 0, runtime)
+        .or_else(|_| player_loop.get_method("GetDefaultPlayerLoop", 0, runtime))?
+        .try_invoke(None, None, runtime)?
+        .ok_or("The player loop is null")?;
+
+    let phases = editor.sub_systems(&root)?.ok_or("The player loop has no phases")?;
+
+    for index in 0..runtime.get_array_length(&phases)? {
+        let phase = editor.get(&phases, index)?;
+        let name = editor.name(&phase)?;
+
+        let Some((_, function)) = PHASES.iter().find(|(phase, _)| *phase == name) else {
+            continue;
+        };
+
+        editor.append(&phase, function)?;
+        editor.set(&phases, index, &phase)?;
+
+        log!("Added frame callback to {}", name)?;
+    }
+
+    // the loop is a struct, passed unboxed
+    let mut params = vec![runtime.unbox_object(&root)?.inner];
+
+    player_loop
+        .get_method("SetPlayerLoop", 1, runtime)?
+        .try_invoke(None, Some(&mut params), runtime)?;
+
+    Ok(())
+}
@@ -0,0 +1,199 @@
+//! keeps track of which mod installed which hook, so they can be removed along with the mod
+//!
+//! a target takes a single detour, so hooking one that is already hooked is refused as a
+//! conflict, naming whoever got there first. mods that want to share a target add patches
+//! through the [`dispatcher`] instead, which owns the target's detour.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use chrono::{DateTime, Local};
+use dobby_rs::Address;
+use lazy_static::lazy_static;
+
+use crate::{errors::hookerr::HookError, log, warn};
+
+use super::{
+    dispatcher::{self, PatchKind, FERREX_OWNER},
+    hook,
+};
+
+#[derive(Debug, Clone)]
+pub struct HookRecord {
+    pub target: usize,
+    pub detour: usize,
+    pub trampoline: usize,
+    pub owner: u64,
+    pub installed: DateTime<Local>,
+}
+
+/// how an entry of the hook table is attached to its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    /// a detour of its own, which replaces the target
+    Detour,
+    Prefix { priority: i32 },
+    Postfix { priority: i32 },
+}
+
+/// an entry of the hook table
+#[derive(Debug, Clone)]
+pub struct HookEntry {
+    pub target: usize,
+    /// the detour, or the handler of a patch
+    pub detour: usize,
+    pub owner: u64,
+    pub kind: HookKind,
+    /// the id of a patch, 0 for a detour
+    pub patch: u64,
+    pub installed: DateTime<Local>,
+}
+
+lazy_static! {
+    static ref HOOKS: Mutex<Vec<HookRecord>> = Mutex::new(Vec::new());
+    /// the names hooks are reported under
+    static ref OWNERS: Mutex<HashMap<u64, String>> = Mutex::new(HashMap::new());
+}
+
+/// names the mod behind `owner`, for conflicts and the hook table
+pub fn register_owner(owner: u64, name: &str) {
+    OWNERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(owner, name.to_string());
+}
+
+pub fn owner_name(owner: u64) -> String {
+    if owner == FERREX_OWNER {
+        return "Ferrex".to_string();
+    }
+
+    OWNERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&owner)
+        .cloned()
+        .unwrap_or_else(|| format!("mod #{}", owner))
+}
+
+/// attaches a hook on behalf of `owner`, returning the trampoline to the original
+pub fn attach(owner: u64, target: Address, detour: Address) -> Result<Address, HookError> {
+    let mut hooks = HOOKS.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let Some(existing) = hooks.iter().find(|h| h.target == target as usize) {
+        let holder = match dispatcher::is_detour(existing.detour) {
+            true => "patches, add a patch as well instead".to_string(),
+            false => owner_name(existing.owner),
+        };
+
+        let _ = warn!(
+            "Conflict: {} tried to hook {:p}, which is already hooked by {}",
+            owner_name(owner),
+            target,
+            holder
+        );
+
+        return Err(HookError::Conflict(format!("{:p}", target), holder));
+    }
+
+    let trampoline = hook::attach(target, detour)?;
+
+    hooks.push(HookRecord {
+        target: target as usize,
+        detour: detour as usize,
+        trampoline: trampoline as usize,
+        owner,
+        installed: Local::now(),
+    });
+
+    Ok(trampoline)
+}
+
+/// detaches a hook, as long as `owner` is the one that installed it
+pub fn detach(owner: u64, target: Address) -> Result<(), HookError> {
+    let mut hooks = HOOKS.lock().unwrap_or_else(|e| e.into_inner());
+
+    let index = hooks
+        .iter()
+        .position(|h| h.target == target as usize && h.owner == owner)
+        .ok_or_else(|| HookError::NotOwned(format!("{:p}", target)))?;
+
+    hook::detach(target)?;
+    hooks.remove(index);
+
+    Ok(())
+}
+
+/// detaches every hook `owner` installed, returning the ones that were removed
+pub fn remove_owner(owner: u64) -> Vec<HookRecord> {
+    let mut hooks = HOOKS.lock().unwrap_or_else(|e| e.into_inner());
+    let (removed, kept) = hooks.drain(..).partition(|h| h.owner == owner);
+    *hooks = kept;
+
+    let removed: Vec<HookRecord> = removed;
+    for record in removed.iter() {
+        let _ = hook::detach(record.target as Address);
+    }
+
+    removed
+}
+
+/// every hook and patch, sorted by target and then in the order they run
+pub fn table() -> Vec<HookEntry> {
+    let mut table: Vec<HookEntry> = HOOKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|h| !dispatcher::is_detour(h.detour))
+        .map(|h| HookEntry {
+            target: h.target,
+            detour: h.detour,
+            owner: h.owner,
+            kind: HookKind::Detour,
+            patch: 0,
+            installed: h.installed,
+        })
+        .collect();
+
+    table.extend(dispatcher::patches().into_iter().map(|patch| HookEntry {
+        target: patch.target,
+        detour: patch.handler,
+        owner: patch.owner,
+        kind: match patch.kind {
+            PatchKind::Prefix => HookKind::Prefix { priority: patch.priority },
+            PatchKind::Postfix => HookKind::Postfix { priority: patch.priority },
+        },
+        patch: patch.id,
+        installed: patch.installed,
+    }));
+
+    // stable, so patches keep the order they run in
+    table.sort_by_key(|entry| entry.target);
+    table
+}
+
+/// logs the hook table
+pub fn log_table() {
+    let table = table();
+
+    if table.is_empty() {
+        return;
+    }
+
+    let _ = log!("{} hooks installed", table.len());
+
+    for entry in table {
+        let kind = match entry.kind {
+            HookKind::Detour => "detour".to_string(),
+            HookKind::Prefix { priority } => format!("prefix {}, priority {}", entry.patch, priority),
+            HookKind::Postfix { priority } => format!("postfix {}, priority {}", entry.patch, priority),
+        };
+
+        let _ = log!(
+            "  {:#x}: {} by {} at {}",
+            entry.target,
+            kind,
+            owner_name(entry.owner),
+            entry.installed.format("%H:%M:%S")
+        );
+    }
+}
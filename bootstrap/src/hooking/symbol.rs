@@ -0,0 +1,36 @@
+//! hooks on functions exported by any module the game has loaded, like UnityPlayer or libc
+//!
+//! modules are only ever looked up, never loaded, so a module that isn't loaded yet can't be
+//! hooked. names may leave out the platform's extension, `UnityPlayer` finds `UnityPlayer.dll`
+//! on windows and `UnityPlayer.so` elsewhere.
+
+use std::{env::consts::DLL_EXTENSION, ffi::c_void};
+
+use dobby_rs::Address;
+use unity_rs::libs::{self, LibError, NativeLibrary};
+
+use crate::errors::DynErr;
+
+use super::registry;
+
+/// the module called `module`, as long as it's loaded
+pub fn module(module: &str) -> Result<NativeLibrary, LibError> {
+    libs::open_loaded(module).or_else(|e| match module.ends_with(&format!(".{}", DLL_EXTENSION)) {
+        true => Err(e),
+        false => libs::open_loaded(format!("{}.{}", module, DLL_EXTENSION)).map_err(|_| e),
+    })
+}
+
+/// the address of `symbol` in `module`
+pub fn find(module_name: &str, symbol: &str) -> Result<Address, DynErr> {
+    let function = module(module_name)?.sym::<c_void>(symbol)?;
+
+    Ok(function.inner)
+}
+
+/// hooks `symbol` in `module` on behalf of `owner`, returning the trampoline to the original
+///
+/// detach it through [`registry::detach`], with the address [`find`] returns
+pub fn attach(owner: u64, module: &str, symbol: &str, detour: Address) -> Result<Address, DynErr> {
+    Ok(registry::attach(owner, find(module, symbol)?, detour)?)
+}
@@ -15,6 +15,9 @@
 // #![cfg_attr(docsrs, feature(doc_cfg))]
 
 mod core;
+mod config;
+mod game;
+mod preferences;
 mod logging;
 mod hooking;
 mod errors;
@@ -23,11 +26,18 @@ mod bindgen;
 pub mod utils;
 mod console;
 
-use ctor::ctor;
+use ctor::{ctor, dtor};
 
 #[ctor]
 fn init(){
     core::init().unwrap_or_else(|e| {
         internal_failure!("Failed to initialize: {}", e);
     });
-}
\ No newline at end of file
+}
+
+/// mods can't be called anymore by now, that happens when unity quits, but whatever they
+/// changed since is still saved
+#[dtor]
+fn shutdown() {
+    preferences::store::save_all();
+}
@@ -0,0 +1,663 @@
+//! the C interface between Ferrex and native mods
+//!
+//! a mod exports `ferrex_mod_info`, and optionally `ferrex_mod_manifest` and any of the
+//! lifecycle callbacks. plugins use the same interface, and may also export `on_early_init`,
+//! which runs before the runtime has executed any managed code.
+//! every struct here is `#[repr(C)]`, and fields are only ever appended, so a mod
+//! built against an older header keeps working as long as the abi version matches.
+//!
+//! callbacks are `extern "C-unwind"`, so a panicking mod unwinds back into Ferrex, which
+//! disables it instead of taking the game down. mods should export them the same way.
+
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+};
+
+use toml::Value;
+
+use crate::{
+    err,
+    errors::preferr::PreferenceError,
+    hooking::{
+        dispatcher::{self, HookCall, PatchKind},
+        method::{self, MethodTarget},
+        registry::{self, HookKind},
+        symbol,
+    },
+    logging::logger::{self, LogLevel},
+    preferences::{schema::Schema, store},
+};
+
+use super::{
+    bus, internal_calls,
+    services::{self, ServiceKind},
+};
+
+/// bumped whenever a struct or callback signature changes in a breaking way
+pub const FERREX_ABI_VERSION: u32 = 1;
+
+pub const MOD_INFO_SYMBOL: &[u8] = b"ferrex_mod_info\0";
+pub const MOD_MANIFEST_SYMBOL: &[u8] = b"ferrex_mod_manifest\0";
+pub const ON_EARLY_INIT_SYMBOL: &[u8] = b"on_early_init\0";
+pub const ON_INIT_SYMBOL: &[u8] = b"on_init\0";
+pub const ON_SCENE_LOADED_SYMBOL: &[u8] = b"on_scene_loaded\0";
+pub const ON_SCENE_EVENT_SYMBOL: &[u8] = b"on_scene_event\0";
+pub const ON_FIXED_UPDATE_SYMBOL: &[u8] = b"on_fixed_update\0";
+pub const ON_UPDATE_SYMBOL: &[u8] = b"on_update\0";
+pub const ON_LATE_UPDATE_SYMBOL: &[u8] = b"on_late_update\0";
+pub const ON_QUIT_SYMBOL: &[u8] = b"on_quit\0";
+
+/// describes a mod, returned by its `ferrex_mod_info` export
+///
+/// the strings have to stay valid for as long as the mod is loaded
+#[derive(Debug)]
+#[repr(C)]
+pub struct FerrexModInfo {
+    /// the abi version the mod was built against, has to be [`FERREX_ABI_VERSION`]
+    pub abi_version: u32,
+    pub name: *const c_char,
+    pub version: *const c_char,
+    pub author: *const c_char,
+}
+
+pub const SCENE_LOADED: u32 = 0;
+pub const SCENE_UNLOADED: u32 = 1;
+pub const ACTIVE_SCENE_CHANGED: u32 = 2;
+
+/// a scene change, passed to `on_scene_event`. the strings are only valid during the call
+#[derive(Debug)]
+#[repr(C)]
+pub struct FerrexSceneEvent {
+    /// one of [`SCENE_LOADED`], [`SCENE_UNLOADED`] or [`ACTIVE_SCENE_CHANGED`]
+    pub kind: u32,
+    /// 0 for a single scene load, 1 for an additive one, only set for [`SCENE_LOADED`]
+    pub mode: u32,
+    /// the scene loaded, unloaded or now active, the build index is -1 if it isn't in the build
+    pub name: *const c_char,
+    pub build_index: i32,
+    /// the scene that was active before, only set for [`ACTIVE_SCENE_CHANGED`]
+    pub previous_name: *const c_char,
+    pub previous_build_index: i32,
+}
+
+pub const HOOK_DETOUR: u32 = 0;
+pub const HOOK_PREFIX: u32 = 1;
+pub const HOOK_POSTFIX: u32 = 2;
+
+/// an entry of the hook table, passed to the `get_hooks` callback. the name is only valid
+/// during the call
+#[derive(Debug)]
+#[repr(C)]
+pub struct FerrexHookInfo {
+    pub target: *mut c_void,
+    /// the detour, or the handler of a patch
+    pub detour: *mut c_void,
+    pub owner: u64,
+    /// the mod that installed the hook, `Ferrex` for Ferrex itself
+    pub owner_name: *const c_char,
+    /// one of [`HOOK_DETOUR`], [`HOOK_PREFIX`] or [`HOOK_POSTFIX`]
+    pub kind: u32,
+    /// only set for prefixes and postfixes
+    pub priority: i32,
+    /// the id `add_patch` returned, 0 for a detour
+    pub patch: u64,
+    /// when the hook was installed, in seconds since the unix epoch
+    pub installed: i64,
+}
+
+/// functions Ferrex offers to mods, passed to `on_init`
+///
+/// every mod gets its own table, functions that act on behalf of a mod take its `owner`,
+/// so whatever they set up can be undone when the mod is unloaded.
+#[derive(Debug)]
+#[repr(C)]
+pub struct FerrexHost {
+    pub abi_version: u32,
+    /// the size of this struct, so mods can tell which fields exist
+    pub size: usize,
+    /// logs a message, `level` is 0 for info, 1 for warnings and 2 for errors
+    pub log: extern "C" fn(level: u8, message: *const c_char),
+    /// identifies the mod this table was handed to
+    pub owner: u64,
+    /// hooks `target`, returns the trampoline to the original or null on failure
+    pub attach_hook: extern "C" fn(owner: u64, target: *mut c_void, detour: *mut c_void) -> *mut c_void,
+    /// removes a hook installed through `attach_hook`
+    pub detach_hook: extern "C" fn(owner: u64, target: *mut c_void) -> bool,
+    /// registers an internal call, `name` is `Namespace.Class::Method`
+    pub add_internal_call: extern "C" fn(owner: u64, name: *const c_char, function: *mut c_void) -> bool,
+    /// declares preference categories and entries from a toml schema,
+    /// see [`crate::preferences::schema`]
+    pub declare_preferences: extern "C" fn(owner: u64, schema: *const c_char) -> bool,
+    /// writes an entry as a toml value into `buffer`, returns the size it needs including
+    /// the terminator, or 0 if the entry doesn't exist
+    pub get_preference: extern "C" fn(owner: u64, category: *const c_char, key: *const c_char, buffer: *mut c_char, size: usize) -> usize,
+    /// sets an entry from a toml value, fails if it doesn't validate
+    pub set_preference: extern "C" fn(owner: u64, category: *const c_char, key: *const c_char, value: *const c_char) -> bool,
+    pub get_bool_preference: extern "C" fn(owner: u64, category: *const c_char, key: *const c_char, out: *mut bool) -> bool,
+    pub set_bool_preference: extern "C" fn(owner: u64, category: *const c_char, key: *const c_char, value: bool) -> bool,
+    pub get_int_preference: extern "C" fn(owner: u64, category: *const c_char, key: *const c_char, out: *mut i64) -> bool,
+    pub set_int_preference: extern "C" fn(owner: u64, category: *const c_char, key: *const c_char, value: i64) -> bool,
+    pub get_float_preference: extern "C" fn(owner: u64, category: *const c_char, key: *const c_char, out: *mut f64) -> bool,
+    pub set_float_preference: extern "C" fn(owner: u64, category: *const c_char, key: *const c_char, value: f64) -> bool,
+    /// like `get_preference`, but writes string and enum entries without quotes
+    pub get_string_preference: extern "C" fn(owner: u64, category: *const c_char, key: *const c_char, buffer: *mut c_char, size: usize) -> usize,
+    pub set_string_preference: extern "C" fn(owner: u64, category: *const c_char, key: *const c_char, value: *const c_char) -> bool,
+    /// calls `callback` whenever a matching entry changes, a null category or key matches any
+    pub on_preference_changed: extern "C" fn(owner: u64, category: *const c_char, key: *const c_char, callback: PreferenceChangedFn, user_data: *mut c_void) -> bool,
+    /// writes changed preferences to disk, they are also saved when the game quits
+    pub save_preferences: extern "C" fn(owner: u64) -> bool,
+    /// publishes a service under `name`, `kind` is 0 for a table of C functions and 1 for a
+    /// pointer to a Rust trait object. `service` has to stay valid while the mod is loaded
+    pub publish_service: extern "C" fn(owner: u64, name: *const c_char, version: *const c_char, kind: u32, service: *const c_void) -> bool,
+    /// looks up a service with the same major version as `version`, and at least that version.
+    /// returns null if there is none
+    pub get_service: extern "C" fn(owner: u64, name: *const c_char, version: *const c_char, kind: u32) -> *const c_void,
+    pub withdraw_service: extern "C" fn(owner: u64, name: *const c_char) -> bool,
+    /// calls `handler` for every message on `topic`, returns the subscription id, or 0 on failure
+    pub subscribe: extern "C" fn(owner: u64, topic: *const c_char, handler: MessageFn, user_data: *mut c_void) -> u64,
+    pub unsubscribe: extern "C" fn(owner: u64, subscription: u64) -> bool,
+    /// sends `data` to everyone subscribed to `topic`, returns how many handlers got it
+    pub publish_message: extern "C" fn(owner: u64, topic: *const c_char, data: *const u8, size: usize) -> usize,
+    /// the native code of a managed method, to hook through `attach_hook`. `params` are the
+    /// comma separated full names of its parameter types, like `System.Int32,System.String`,
+    /// and may be null if the method isn't overloaded. returns null if it can't be found
+    pub find_method: extern "C" fn(assembly: *const c_char, namespace: *const c_char, class: *const c_char, method: *const c_char, params: *const c_char) -> *mut c_void,
+    /// [`find_method`](Self::find_method) and [`attach_hook`](Self::attach_hook) in one,
+    /// detach it with `detach_hook` and the address `find_method` returns
+    pub attach_method_hook: extern "C" fn(owner: u64, assembly: *const c_char, namespace: *const c_char, class: *const c_char, method: *const c_char, params: *const c_char, detour: *mut c_void) -> *mut c_void,
+    /// patches `target` without taking it over, so other mods can patch it too. `kind` is 0 for
+    /// a prefix and 1 for a postfix, higher priorities run first. returns the patch id, or 0
+    /// on failure. see [`crate::hooking::dispatcher`]
+    pub add_patch: extern "C" fn(owner: u64, target: *mut c_void, kind: u32, priority: i32, handler: PatchFn, user_data: *mut c_void) -> u64,
+    pub remove_patch: extern "C" fn(owner: u64, id: u64) -> bool,
+    /// calls `callback` for every hook and patch, sorted by target, returns how many there are
+    pub get_hooks: extern "C" fn(callback: HookInfoFn, user_data: *mut c_void) -> usize,
+    /// the address of a function exported by a module the game has loaded, to hook through
+    /// `attach_hook` or `add_patch`. the module is never loaded, and its extension may be left
+    /// out, like `UnityPlayer`. returns null if either can't be found
+    pub find_symbol: extern "C" fn(module: *const c_char, symbol: *const c_char) -> *mut c_void,
+    /// [`find_symbol`](Self::find_symbol) and [`attach_hook`](Self::attach_hook) in one,
+    /// detach it with `detach_hook` and the address `find_symbol` returns
+    pub attach_symbol_hook: extern "C" fn(owner: u64, module: *const c_char, symbol: *const c_char, detour: *mut c_void) -> *mut c_void,
+}
+
+pub type ModInfoFn = unsafe extern "C-unwind" fn() -> *const FerrexModInfo;
+/// returns the mod's manifest as a toml string, see [`crate::mods::manifest::ModManifest`]
+pub type ModManifestFn = unsafe extern "C-unwind" fn() -> *const c_char;
+/// only called for plugins, the host table is the same one later passed to `on_init`
+pub type OnEarlyInitFn = unsafe extern "C-unwind" fn(host: *const FerrexHost);
+pub type OnInitFn = unsafe extern "C-unwind" fn(host: *const FerrexHost);
+pub type OnSceneLoadedFn = unsafe extern "C-unwind" fn(build_index: i32, name: *const c_char);
+/// every scene change, mods exporting both get scene loads twice
+pub type OnSceneEventFn = unsafe extern "C-unwind" fn(event: *const FerrexSceneEvent);
+/// the frame callbacks run after the game's scripts got their FixedUpdate, Update and LateUpdate
+pub type OnFixedUpdateFn = unsafe extern "C-unwind" fn();
+pub type OnUpdateFn = unsafe extern "C-unwind" fn();
+pub type OnLateUpdateFn = unsafe extern "C-unwind" fn();
+pub type OnQuitFn = unsafe extern "C-unwind" fn();
+/// a prefix or postfix, `call` is only valid during the call
+pub type PatchFn = unsafe extern "C-unwind" fn(call: *mut HookCall, user_data: *mut c_void);
+/// `info` is only valid during the call
+pub type HookInfoFn = unsafe extern "C-unwind" fn(info: *const FerrexHookInfo, user_data: *mut c_void);
+/// `data` is only valid during the call
+pub type MessageFn = unsafe extern "C-unwind" fn(
+    topic: *const c_char,
+    data: *const u8,
+    size: usize,
+    user_data: *mut c_void,
+);
+/// `old` and `new` are toml values, all strings are only valid during the call
+pub type PreferenceChangedFn = unsafe extern "C-unwind" fn(
+    category: *const c_char,
+    key: *const c_char,
+    old: *const c_char,
+    new: *const c_char,
+    user_data: *mut c_void,
+);
+
+extern "C" fn host_log(level: u8, message: *const c_char) {
+    if message.is_null() {
+        return;
+    }
+
+    let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+    let level = LogLevel::try_from(level).unwrap_or(LogLevel::Info);
+
+    let _ = logger::log_console_file(level, &message);
+}
+
+extern "C" fn host_attach_hook(owner: u64, target: *mut c_void, detour: *mut c_void) -> *mut c_void {
+    registry::attach(owner, target, detour).unwrap_or_else(|e| {
+        let _ = err!("Failed to attach hook: {}", e.to_string());
+        std::ptr::null_mut()
+    })
+}
+
+extern "C" fn host_detach_hook(owner: u64, target: *mut c_void) -> bool {
+    registry::detach(owner, target)
+        .map_err(|e| err!("Failed to detach hook: {}", e.to_string()))
+        .is_ok()
+}
+
+fn method_target(
+    assembly: *const c_char,
+    namespace: *const c_char,
+    class: *const c_char,
+    method: *const c_char,
+    params: *const c_char,
+) -> Option<MethodTarget> {
+    Some(MethodTarget {
+        assembly: read_arg(assembly)?,
+        namespace: read_arg(namespace).unwrap_or_default(),
+        class: read_arg(class)?,
+        method: read_arg(method)?,
+        params: read_arg(params).map(|params| {
+            params
+                .split(',')
+                .map(str::trim)
+                .filter(|param| !param.is_empty())
+                .map(str::to_string)
+                .collect()
+        }),
+    })
+}
+
+extern "C" fn host_find_method(
+    assembly: *const c_char,
+    namespace: *const c_char,
+    class: *const c_char,
+    method: *const c_char,
+    params: *const c_char,
+) -> *mut c_void {
+    let Some(target) = method_target(assembly, namespace, class, method, params) else {
+        return std::ptr::null_mut();
+    };
+
+    method::find(&target).unwrap_or_else(|e| {
+        let _ = err!("Failed to find {}: {}", target, e.to_string());
+        std::ptr::null_mut()
+    })
+}
+
+extern "C" fn host_attach_method_hook(
+    owner: u64,
+    assembly: *const c_char,
+    namespace: *const c_char,
+    class: *const c_char,
+    method: *const c_char,
+    params: *const c_char,
+    detour: *mut c_void,
+) -> *mut c_void {
+    let Some(target) = method_target(assembly, namespace, class, method, params) else {
+        return std::ptr::null_mut();
+    };
+
+    method::attach(owner, &target, detour).unwrap_or_else(|e| {
+        let _ = err!("Failed to hook {}: {}", target, e.to_string());
+        std::ptr::null_mut()
+    })
+}
+
+extern "C" fn host_find_symbol(module: *const c_char, symbol: *const c_char) -> *mut c_void {
+    let (Some(module), Some(symbol)) = (read_arg(module), read_arg(symbol)) else {
+        return std::ptr::null_mut();
+    };
+
+    symbol::find(&module, &symbol).unwrap_or_else(|e| {
+        let _ = err!("Failed to find {} in {}: {}", symbol, module, e.to_string());
+        std::ptr::null_mut()
+    })
+}
+
+extern "C" fn host_attach_symbol_hook(owner: u64, module: *const c_char, symbol: *const c_char, detour: *mut c_void) -> *mut c_void {
+    let (Some(module), Some(symbol)) = (read_arg(module), read_arg(symbol)) else {
+        return std::ptr::null_mut();
+    };
+
+    symbol::attach(owner, &module, &symbol, detour).unwrap_or_else(|e| {
+        let _ = err!("Failed to hook {} in {}: {}", symbol, module, e.to_string());
+        std::ptr::null_mut()
+    })
+}
+
+extern "C" fn host_add_patch(
+    owner: u64,
+    target: *mut c_void,
+    kind: u32,
+    priority: i32,
+    handler: PatchFn,
+    user_data: *mut c_void,
+) -> u64 {
+    let kind = match PatchKind::try_from(kind) {
+        Ok(kind) => kind,
+        Err(e) => {
+            let _ = err!("Failed to patch {:p}: {}", target, e);
+            return 0;
+        }
+    };
+
+    // the mod promises user_data stays valid while it's loaded, patches are removed on unload
+    let user_data = user_data as usize;
+    let function = handler as *mut c_void;
+    let handler: dispatcher::Handler = Arc::new(move |call| unsafe { handler(call, user_data as *mut c_void) });
+
+    dispatcher::add(owner, target, kind, priority, handler, function).unwrap_or_else(|e| {
+        let _ = err!("Failed to patch {:p}: {}", target, e.to_string());
+        0
+    })
+}
+
+extern "C" fn host_remove_patch(owner: u64, id: u64) -> bool {
+    dispatcher::remove(owner, id)
+        .map_err(|e| err!("Failed to remove patch {}: {}", id, e.to_string()))
+        .is_ok()
+}
+
+extern "C" fn host_get_hooks(callback: HookInfoFn, user_data: *mut c_void) -> usize {
+    let table = registry::table();
+
+    for entry in table.iter() {
+        let owner_name = CString::new(registry::owner_name(entry.owner)).unwrap_or_default();
+
+        let (kind, priority) = match entry.kind {
+            HookKind::Detour => (HOOK_DETOUR, 0),
+            HookKind::Prefix { priority } => (HOOK_PREFIX, priority),
+            HookKind::Postfix { priority } => (HOOK_POSTFIX, priority),
+        };
+
+        let info = FerrexHookInfo {
+            target: entry.target as *mut c_void,
+            detour: entry.detour as *mut c_void,
+            owner: entry.owner,
+            owner_name: owner_name.as_ptr(),
+            kind,
+            priority,
+            patch: entry.patch,
+            installed: entry.installed.timestamp(),
+        };
+
+        if panic::catch_unwind(AssertUnwindSafe(|| unsafe { callback(&info, user_data) })).is_err() {
+            let _ = err!("A get_hooks callback panicked");
+            break;
+        }
+    }
+
+    table.len()
+}
+
+extern "C" fn host_add_internal_call(owner: u64, name: *const c_char, function: *mut c_void) -> bool {
+    if name.is_null() {
+        return false;
+    }
+
+    let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+
+    internal_calls::add(owner, &name, function)
+        .map_err(|e| err!("Failed to add internal call {}: {}", name, e.to_string()))
+        .is_ok()
+}
+
+fn read_arg(ptr: *const c_char) -> Option<String> {
+    match ptr.is_null() {
+        true => None,
+        false => Some(unsafe { CStr::from_ptr(ptr) }.to_string_lossy().to_string()),
+    }
+}
+
+/// copies `value` into `buffer` if it fits, returns the size needed including the terminator
+fn write_buffer(value: &str, buffer: *mut c_char, size: usize) -> usize {
+    let needed = value.len() + 1;
+
+    if !buffer.is_null() && size >= needed {
+        unsafe {
+            std::ptr::copy_nonoverlapping(value.as_ptr().cast(), buffer, value.len());
+            *buffer.add(value.len()) = 0;
+        }
+    }
+
+    needed
+}
+
+fn report_preference<T>(what: &str, result: Result<T, PreferenceError>) -> Option<T> {
+    result
+        .map_err(|e| err!("Failed to {}: {}", what, e.to_string()))
+        .ok()
+}
+
+fn get_preference_value(owner: u64, category: *const c_char, key: *const c_char) -> Option<Value> {
+    let category = read_arg(category)?;
+    let key = read_arg(key)?;
+
+    report_preference("read preference", store::get(owner, &category, &key))
+}
+
+fn set_preference_value(owner: u64, category: *const c_char, key: *const c_char, value: Value) -> bool {
+    let (Some(category), Some(key)) = (read_arg(category), read_arg(key)) else {
+        return false;
+    };
+
+    report_preference("set preference", store::set(owner, &category, &key, value)).is_some()
+}
+
+extern "C" fn host_declare_preferences(owner: u64, schema: *const c_char) -> bool {
+    let Some(schema) = read_arg(schema) else {
+        return false;
+    };
+
+    let result = Schema::parse(&schema)
+        .map_err(PreferenceError::from)
+        .and_then(|schema| store::declare(owner, schema));
+
+    report_preference("declare preferences", result).is_some()
+}
+
+extern "C" fn host_get_preference(owner: u64, category: *const c_char, key: *const c_char, buffer: *mut c_char, size: usize) -> usize {
+    match get_preference_value(owner, category, key) {
+        Some(value) => write_buffer(&value.to_string(), buffer, size),
+        None => 0,
+    }
+}
+
+extern "C" fn host_set_preference(owner: u64, category: *const c_char, key: *const c_char, value: *const c_char) -> bool {
+    let Some(value) = read_arg(value) else {
+        return false;
+    };
+
+    match report_preference("parse preference", store::parse_literal(&value)) {
+        Some(value) => set_preference_value(owner, category, key, value),
+        None => false,
+    }
+}
+
+macro_rules! typed_preferences {
+    ($($ty:ty => $variant:ident, $get:ident, $set:ident;)*) => {
+        $(
+            extern "C" fn $get(owner: u64, category: *const c_char, key: *const c_char, out: *mut $ty) -> bool {
+                if out.is_null() {
+                    return false;
+                }
+
+                match get_preference_value(owner, category, key) {
+                    Some(Value::$variant(value)) => {
+                        unsafe { *out = value };
+                        true
+                    }
+                    _ => false,
+                }
+            }
+
+            extern "C" fn $set(owner: u64, category: *const c_char, key: *const c_char, value: $ty) -> bool {
+                set_preference_value(owner, category, key, Value::$variant(value))
+            }
+        )*
+    };
+}
+
+typed_preferences! {
+    bool => Boolean, host_get_bool_preference, host_set_bool_preference;
+    i64 => Integer, host_get_int_preference, host_set_int_preference;
+    f64 => Float, host_get_float_preference, host_set_float_preference;
+}
+
+extern "C" fn host_get_string_preference(owner: u64, category: *const c_char, key: *const c_char, buffer: *mut c_char, size: usize) -> usize {
+    match get_preference_value(owner, category, key) {
+        Some(Value::String(value)) => write_buffer(&value, buffer, size),
+        _ => 0,
+    }
+}
+
+extern "C" fn host_set_string_preference(owner: u64, category: *const c_char, key: *const c_char, value: *const c_char) -> bool {
+    match read_arg(value) {
+        Some(value) => set_preference_value(owner, category, key, Value::String(value)),
+        None => false,
+    }
+}
+
+extern "C" fn host_on_preference_changed(owner: u64, category: *const c_char, key: *const c_char, callback: PreferenceChangedFn, user_data: *mut c_void) -> bool {
+    // the mod promises user_data stays valid while it's loaded, callbacks are dropped on unload
+    let user_data = user_data as usize;
+
+    let callback: store::Callback = Arc::new(move |change| {
+        let strings = [
+            change.category.clone(),
+            change.key.clone(),
+            change.old.to_string(),
+            change.new.to_string(),
+        ]
+        .map(|s| CString::new(s).unwrap_or_default());
+
+        unsafe {
+            callback(
+                strings[0].as_ptr(),
+                strings[1].as_ptr(),
+                strings[2].as_ptr(),
+                strings[3].as_ptr(),
+                user_data as *mut c_void,
+            )
+        }
+    });
+
+    let category = read_arg(category);
+    let key = read_arg(key);
+
+    report_preference(
+        "watch preference",
+        store::watch(owner, category.as_deref(), key.as_deref(), callback),
+    )
+    .is_some()
+}
+
+extern "C" fn host_save_preferences(owner: u64) -> bool {
+    report_preference("save preferences", store::save(owner)).is_some()
+}
+
+extern "C" fn host_publish_service(owner: u64, name: *const c_char, version: *const c_char, kind: u32, service: *const c_void) -> bool {
+    let (Some(name), Some(version)) = (read_arg(name), read_arg(version)) else {
+        return false;
+    };
+
+    ServiceKind::try_from(kind)
+        .map_err(Into::into)
+        .and_then(|kind| services::publish(owner, &name, &version, kind, service))
+        .map_err(|e| err!("Failed to publish service {}: {}", name, e.to_string()))
+        .is_ok()
+}
+
+extern "C" fn host_get_service(owner: u64, name: *const c_char, version: *const c_char, kind: u32) -> *const c_void {
+    let (Some(name), Some(version)) = (read_arg(name), read_arg(version)) else {
+        return std::ptr::null();
+    };
+
+    ServiceKind::try_from(kind)
+        .map_err(Into::into)
+        .and_then(|kind| services::lookup(owner, &name, &version, kind))
+        .unwrap_or_else(|e| {
+            let _ = err!("Failed to get service {}: {}", name, e.to_string());
+            None
+        })
+        .unwrap_or(std::ptr::null())
+}
+
+extern "C" fn host_withdraw_service(owner: u64, name: *const c_char) -> bool {
+    let Some(name) = read_arg(name) else {
+        return false;
+    };
+
+    services::withdraw(owner, &name)
+        .map_err(|e| err!("Failed to withdraw service {}: {}", name, e.to_string()))
+        .is_ok()
+}
+
+extern "C" fn host_subscribe(owner: u64, topic: *const c_char, handler: MessageFn, user_data: *mut c_void) -> u64 {
+    let Some(topic) = read_arg(topic) else {
+        return 0;
+    };
+
+    // the mod promises user_data stays valid while it's loaded, handlers are dropped on unload
+    let user_data = user_data as usize;
+
+    let handler: bus::Handler = Arc::new(move |topic, data| {
+        let topic = CString::new(topic).unwrap_or_default();
+        unsafe { handler(topic.as_ptr(), data.as_ptr(), data.len(), user_data as *mut c_void) }
+    });
+
+    bus::subscribe(owner, &topic, handler)
+}
+
+extern "C" fn host_unsubscribe(owner: u64, subscription: u64) -> bool {
+    bus::unsubscribe(owner, subscription)
+}
+
+extern "C" fn host_publish_message(_owner: u64, topic: *const c_char, data: *const u8, size: usize) -> usize {
+    let Some(topic) = read_arg(topic) else {
+        return 0;
+    };
+
+    let data = match data.is_null() {
+        true => &[][..],
+        false => unsafe { std::slice::from_raw_parts(data, size) },
+    };
+
+    bus::publish(&topic, data)
+}
+
+/// the host table for the mod identified by `owner`
+pub fn host(owner: u64) -> FerrexHost {
+    FerrexHost {
+        abi_version: FERREX_ABI_VERSION,
+        size: std::mem::size_of::<FerrexHost>(),
+        log: host_log,
+        owner,
+        attach_hook: host_attach_hook,
+        detach_hook: host_detach_hook,
+        add_internal_call: host_add_internal_call,
+        declare_preferences: host_declare_preferences,
+        get_preference: host_get_preference,
+        set_preference: host_set_preference,
+        get_bool_preference: host_get_bool_preference,
+        set_bool_preference: host_set_bool_preference,
+        get_int_preference: host_get_int_preference,
+        set_int_preference: host_set_int_preference,
+        get_float_preference: host_get_float_preference,
+        set_float_preference: host_set_float_preference,
+        get_string_preference: host_get_string_preference,
+        set_string_preference: host_set_string_preference,
+        on_preference_changed: host_on_preference_changed,
+        save_preferences: host_save_preferences,
+        publish_service: host_publish_service,
+        get_service: host_get_service,
+        withdraw_service: host_withdraw_service,
+        subscribe: host_subscribe,
+        unsubscribe: host_unsubscribe,
+        publish_message: host_publish_message,
+        find_method: host_find_method,
+        attach_method_hook: host_attach_method_hook,
+        add_patch: host_add_patch,
+        remove_patch: host_remove_patch,
+        get_hooks: host_get_hooks,
+        find_symbol: host_find_symbol,
+        attach_symbol_hook: host_attach_symbol_hook,
+    }
+}
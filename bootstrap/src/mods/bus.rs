@@ -0,0 +1,95 @@
+//! a publish/subscribe message bus between mods
+//!
+//! topics are plain strings and payloads are bytes, what they mean is up to the mods using them.
+//! messages are delivered synchronously, on the thread that published them.
+
+use std::{
+    collections::HashMap,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+};
+
+use lazy_static::lazy_static;
+
+use crate::err;
+
+pub type Handler = Arc<dyn Fn(&str, &[u8]) + Send + Sync>;
+
+struct Subscription {
+    owner: u64,
+    topic: String,
+    handler: Handler,
+}
+
+#[derive(Default)]
+struct Bus {
+    next_id: u64,
+    subscriptions: HashMap<u64, Subscription>,
+}
+
+lazy_static! {
+    static ref BUS: Mutex<Bus> = Mutex::new(Bus::default());
+}
+
+/// calls `handler` for every message on `topic`, returns the id to unsubscribe with
+pub fn subscribe(owner: u64, topic: &str, handler: Handler) -> u64 {
+    let mut bus = BUS.lock().unwrap_or_else(|e| e.into_inner());
+
+    bus.next_id += 1;
+    let id = bus.next_id;
+
+    bus.subscriptions.insert(
+        id,
+        Subscription {
+            owner,
+            topic: topic.to_string(),
+            handler,
+        },
+    );
+
+    id
+}
+
+/// removes a subscription, only its owner may do so
+pub fn unsubscribe(owner: u64, id: u64) -> bool {
+    let mut bus = BUS.lock().unwrap_or_else(|e| e.into_inner());
+
+    match bus.subscriptions.get(&id) {
+        Some(subscription) if subscription.owner == owner => {
+            bus.subscriptions.remove(&id);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// sends `payload` to everyone subscribed to `topic`, returns how many handlers got it
+pub fn publish(topic: &str, payload: &[u8]) -> usize {
+    let handlers: Vec<Handler> = BUS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .subscriptions
+        .values()
+        .filter(|s| s.topic == topic)
+        .map(|s| s.handler.clone())
+        .collect();
+
+    // called without the lock held, so handlers can publish and subscribe themselves
+    for handler in handlers.iter() {
+        if panic::catch_unwind(AssertUnwindSafe(|| handler(topic, payload))).is_err() {
+            let _ = err!("A handler for {} panicked", topic);
+        }
+    }
+
+    handlers.len()
+}
+
+/// drops every subscription of an unloaded or disabled mod
+pub fn remove_owner(owner: u64) -> usize {
+    let mut bus = BUS.lock().unwrap_or_else(|e| e.into_inner());
+    let before = bus.subscriptions.len();
+
+    bus.subscriptions.retain(|_, s| s.owner != owner);
+
+    before - bus.subscriptions.len()
+}
@@ -0,0 +1,71 @@
+//! internal calls registered by mods
+//!
+//! the runtime caches an internal call once it's been resolved, and there is no way to
+//! unregister one. so the library of an unloaded mod stays mapped, and when a reloaded
+//! build registers the same name again, the old function is hooked over to the new one.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::c_void,
+    sync::Mutex,
+};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    core,
+    errors::DynErr,
+    hooking::{dispatcher::FERREX_OWNER, registry},
+};
+
+#[derive(Debug)]
+struct InternalCall {
+    owner: u64,
+    function: usize,
+}
+
+#[derive(Debug, Default)]
+struct InternalCalls {
+    calls: HashMap<String, InternalCall>,
+    /// owners that have been unloaded
+    retired: HashSet<u64>,
+}
+
+lazy_static! {
+    static ref CALLS: Mutex<InternalCalls> = Mutex::new(InternalCalls::default());
+}
+
+pub fn add(owner: u64, name: &str, function: *mut c_void) -> Result<(), DynErr> {
+    let mut calls = CALLS.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let Some(previous) = calls.calls.get(name) {
+        if previous.owner != owner && !calls.retired.contains(&previous.owner) {
+            return Err(format!("Internal call {} is already registered by another mod", name).into());
+        }
+
+        // callers that already resolved the old function end up in the new one
+        if previous.owner != owner {
+            let _ = registry::attach(FERREX_OWNER, previous.function as *mut c_void, function)?;
+        }
+    }
+
+    core::get_runtime()?.add_internal_call(name, function)?;
+
+    calls.calls.insert(
+        name.to_string(),
+        InternalCall {
+            owner,
+            function: function as usize,
+        },
+    );
+
+    Ok(())
+}
+
+/// marks `owner` as unloaded, returns whether it registered internal calls,
+/// in which case its library has to stay mapped
+pub fn retire(owner: u64) -> bool {
+    let mut calls = CALLS.lock().unwrap_or_else(|e| e.into_inner());
+    calls.retired.insert(owner);
+    calls.calls.values().any(|call| call.owner == owner)
+}
@@ -0,0 +1,259 @@
+//! managed mods, .NET assemblies loaded into the game's mono domain
+//!
+//! the entry type is the class marked with a `FerrexMod` attribute, or failing that a class
+//! named `Mod` in the namespace named after the assembly. it needs a parameterless
+//! constructor, and may declare any of these, which are called like their native counterparts:
+//!
+//! ```csharp
+//! [FerrexMod]
+//! public class MyMod
+//! {
+//!     public void OnInit() {}
+//!     public void OnSceneLoaded(int buildIndex, string name) {}
+//!     public void OnSceneUnloaded(int buildIndex, string name) {}
+//!     public void OnActiveSceneChanged(int previousBuildIndex, string previousName, int buildIndex, string name) {}
+//!     public void OnFixedUpdate() {}
+//!     public void OnUpdate() {}
+//!     public void OnLateUpdate() {}
+//!     public void OnQuit() {}
+//! }
+//! ```
+//!
+//! the attribute is matched by name, so mods can simply declare it themselves.
+//! assemblies can't be unloaded from the root domain, so managed mods aren't hot reloaded.
+
+use std::{
+    ffi::c_void,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use object::{pe::IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR, read::pe::{ImageNtHeaders, PeFile, PeFile32, PeFile64}};
+use unity_rs::{
+    common::{assembly::UnityAssembly, class::UnityClass, method::UnityMethod, object::UnityObject},
+    runtime::{FerrexRuntime, RuntimeType},
+};
+
+use crate::{
+    core, err,
+    errors::{moderr::ModError, DynErr},
+};
+
+use super::{
+    manifest::ModManifest,
+    scenes::SceneEvent,
+    traits::{self, FerrexMod},
+    trust,
+};
+
+const ENTRY_ATTRIBUTES: [&str; 2] = ["FerrexModAttribute", "FerrexMod"];
+const ENTRY_CLASS: &str = "Mod";
+
+#[derive(Debug)]
+pub struct ManagedMod {
+    pub manifest: ModManifest,
+    pub path: PathBuf,
+    owner: u64,
+    /// an instance of the entry type
+    instance: UnityObject,
+    /// keeps the instance alive, and in place
+    handle: u32,
+
+    on_init: Option<UnityMethod>,
+    on_scene_loaded: Option<UnityMethod>,
+    on_scene_unloaded: Option<UnityMethod>,
+    on_active_scene_changed: Option<UnityMethod>,
+    on_fixed_update: Option<UnityMethod>,
+    on_update: Option<UnityMethod>,
+    on_late_update: Option<UnityMethod>,
+    on_quit: Option<UnityMethod>,
+}
+
+fn has_cli_header<Pe: ImageNtHeaders>(pe: &PeFile<Pe>) -> bool {
+    pe.data_directory(IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR)
+        .is_some_and(|directory| directory.size.get(object::LittleEndian) != 0)
+}
+
+/// whether `path` is a .NET assembly rather than a native library, both end in `.dll`
+pub fn is_managed(path: &Path) -> bool {
+    let Ok(data) = fs::read(path) else {
+        return false;
+    };
+
+    // assemblies built for any cpu are 32 bit images, even on 64 bit games
+    match object::FileKind::parse(&*data) {
+        Ok(object::FileKind::Pe32) => PeFile32::parse(&*data).is_ok_and(|pe| has_cli_header(&pe)),
+        Ok(object::FileKind::Pe64) => PeFile64::parse(&*data).is_ok_and(|pe| has_cli_header(&pe)),
+        _ => false,
+    }
+}
+
+/// the class marked as the entry type, or the one named by convention
+fn entry_type(assembly: &UnityAssembly, runtime: &FerrexRuntime) -> Result<Option<UnityClass>, DynErr> {
+    let classes = assembly.get_image(runtime)?.get_classes(runtime)?;
+    let mut marked = Vec::new();
+
+    for class in classes.iter() {
+        for attribute in class.get_attributes(runtime)? {
+            if ENTRY_ATTRIBUTES.contains(&attribute.get_name(runtime)?.as_str()) {
+                marked.push(*class);
+            }
+        }
+    }
+
+    if marked.len() > 1 {
+        return Err("more than one class is marked with [FerrexMod]".into());
+    }
+
+    if let Some(class) = marked.pop() {
+        return Ok(Some(class));
+    }
+
+    let namespace = assembly.get_name(runtime)?;
+
+    for class in classes {
+        if class.get_name(runtime)? == ENTRY_CLASS && class.get_namespace(runtime)? == namespace {
+            return Ok(Some(class));
+        }
+    }
+
+    Ok(None)
+}
+
+impl ManagedMod {
+    /// opens the assembly at `path` and creates an instance of its entry type
+    pub fn load(path: &Path) -> Result<Self, DynErr> {
+        let file_name = path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let runtime = core::get_runtime()?;
+        let runtime_type = runtime.get_type();
+
+        if !matches!(runtime_type, RuntimeType::Mono(_)) {
+            return Err(ModError::UnsupportedRuntime(file_name, runtime_type.to_string()).into());
+        }
+
+        // mono maps the file itself, there is no copy to check
+        trust::verify(path)?;
+
+        let assembly = UnityAssembly::open(path, runtime)?;
+
+        let class = entry_type(&assembly, runtime)?.ok_or_else(|| {
+            ModError::MissingEntryType(file_name.clone(), format!("{}.{}", assembly.get_name(runtime).unwrap_or_default(), ENTRY_CLASS))
+        })?;
+
+        let constructor = class
+            .get_method(".ctor", 0, runtime)
+            .map_err(|_| format!("{} has no parameterless constructor", class.get_name(runtime).unwrap_or_default()))?;
+
+        let manifest = match ModManifest::sidecar(path)? {
+            Some(sidecar) => sidecar,
+            None => ModManifest::new(&assembly.get_name(runtime)?, &assembly.get_version(runtime)?, ""),
+        };
+
+        let instance = class.new_object(runtime)?;
+
+        let managed = ManagedMod {
+            manifest,
+            path: path.to_path_buf(),
+            owner: traits::next_owner(),
+            instance,
+            handle: instance.new_gc_handle(true, runtime)?,
+            on_init: class.get_method("OnInit", 0, runtime).ok(),
+            on_scene_loaded: class.get_method("OnSceneLoaded", 2, runtime).ok(),
+            on_scene_unloaded: class.get_method("OnSceneUnloaded", 2, runtime).ok(),
+            on_active_scene_changed: class.get_method("OnActiveSceneChanged", 4, runtime).ok(),
+            on_fixed_update: class.get_method("OnFixedUpdate", 0, runtime).ok(),
+            on_update: class.get_method("OnUpdate", 0, runtime).ok(),
+            on_late_update: class.get_method("OnLateUpdate", 0, runtime).ok(),
+            on_quit: class.get_method("OnQuit", 0, runtime).ok(),
+        };
+
+        // constructed last, so the handle is freed if the constructor throws
+        constructor.try_invoke(Some(&managed.instance), None, runtime)?;
+
+        Ok(managed)
+    }
+
+    fn call(&self, method: Option<&UnityMethod>, params: Option<&mut Vec<*mut c_void>>) -> Result<(), DynErr> {
+        if let Some(method) = method {
+            method.try_invoke(Some(&self.instance), params, core::get_runtime()?)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for ManagedMod {
+    fn drop(&mut self) {
+        let freed = core::get_runtime().and_then(|runtime| Ok(runtime.free_gc_handle(self.handle)?));
+
+        if let Err(e) = freed {
+            let _ = err!("Failed to release {}: {}", self.manifest.display_name(), e.to_string());
+        }
+    }
+}
+
+impl FerrexMod for ManagedMod {
+    fn owner(&self) -> u64 {
+        self.owner
+    }
+
+    fn manifest(&self) -> &ModManifest {
+        &self.manifest
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn init(&self) -> Result<(), DynErr> {
+        self.call(self.on_init.as_ref(), None)
+    }
+
+    fn scene_event(&self, event: &SceneEvent) -> Result<(), DynErr> {
+        let (method, scenes) = match event {
+            SceneEvent::Loaded { scene, .. } => (self.on_scene_loaded.as_ref(), vec![scene]),
+            SceneEvent::Unloaded { scene } => (self.on_scene_unloaded.as_ref(), vec![scene]),
+            SceneEvent::ActiveChanged { previous, current } => {
+                (self.on_active_scene_changed.as_ref(), vec![previous, current])
+            }
+        };
+
+        if method.is_none() {
+            return Ok(());
+        }
+
+        let runtime = core::get_runtime()?;
+
+        // every scene is passed as its build index and name
+        let mut build_indices: Vec<i32> = scenes.iter().map(|scene| scene.build_index).collect();
+        let mut params = Vec::new();
+
+        for (scene, build_index) in scenes.iter().zip(build_indices.iter_mut()) {
+            params.push(build_index as *mut i32 as *mut c_void);
+            // empty for no scene, which the first active scene change comes from
+            params.push(runtime.new_string(&scene.name)?.inner);
+        }
+
+        self.call(method, Some(&mut params))
+    }
+
+    fn fixed_update(&self) -> Result<(), DynErr> {
+        self.call(self.on_fixed_update.as_ref(), None)
+    }
+
+    fn update(&self) -> Result<(), DynErr> {
+        self.call(self.on_update.as_ref(), None)
+    }
+
+    fn late_update(&self) -> Result<(), DynErr> {
+        self.call(self.on_late_update.as_ref(), None)
+    }
+
+    fn quit(&self) -> Result<(), DynErr> {
+        self.call(self.on_quit.as_ref(), None)
+    }
+}
@@ -1,54 +1,553 @@
 use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::VecDeque,
     error::Error,
-    fs::{self, DirEntry}, env::consts::DLL_EXTENSION,
+    fs, env::consts::DLL_EXTENSION,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::Mutex,
 };
 
-use libloading::Library;
+use lazy_static::lazy_static;
 
-use crate::{log, bindgen};
+use crate::{log, err, warn, bindgen, config, console::commands, errors::{moderr::ModError, DynErr}, hooking::{dispatcher, registry}, preferences::store as preferences};
+
+use super::{
+    bus, internal_calls,
+    managed::{self, ManagedMod},
+    manifest::{self, Skipped},
+    native::{self, NativeMod},
+    reload,
+    scenes::SceneEvent,
+    services,
+    traits::FerrexMod,
+    userlibs,
+    wasm::WasmMod,
+};
+
+lazy_static! {
+    /// the loaded plugins and mods, kept alive until the process exits
+    static ref MANAGER: Mutex<Option<ModManager>> = Mutex::new(None);
+}
+
+/// a mod that failed to load, or was disabled after failing in a callback
+#[derive(Debug, Clone)]
+pub struct Failed {
+    pub name: String,
+    pub reason: String,
+}
 
 pub struct ModManager {
-    pub plugins: Vec<Library>
+    /// in load order, plugins first, then mods with their dependencies before them
+    pub mods: Vec<Box<dyn FerrexMod>>,
+    pub failed: Vec<Failed>,
+    pub skipped: Vec<Skipped>,
+    /// mods that failed in a callback, they stay loaded since their code may still be referenced
+    disabled: Vec<Box<dyn FerrexMod>>,
+}
+
+impl std::fmt::Debug for ModManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModManager")
+            .field("mods", &self.mods.iter().map(|m| m.name()).collect::<Vec<_>>())
+            .field("failed", &self.failed)
+            .field("skipped", &self.skipped)
+            .finish()
+    }
+}
+
+/// every file in `dir` with the given extension
+fn files_with_extension(dir: &Path, extension: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|d| d.path())
+        .filter(|p| p.extension().is_some_and(|e| e == extension))
+        .collect();
+
+    files.sort();
+
+    Ok(files)
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(message) => message.clone(),
+            None => "unknown panic".to_string(),
+        },
+    }
+}
+
+/// runs `f`, turning both errors and panics into an error message
+fn isolate<T>(f: impl FnOnce() -> Result<T, DynErr>) -> Result<T, DynErr> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result,
+        Err(payload) => Err(format!("panicked: {}", panic_message(payload.as_ref())).into()),
+    }
+}
+
+/// takes back everything other mods and the game could still reach a mod through, returns
+/// whether another mod used one of its services, in which case its code has to stay mapped
+fn release(loaded: &dyn FerrexMod) -> bool {
+    let owner = loaded.owner();
+
+    bus::remove_owner(owner);
+    let shared = services::remove_owner(owner);
+
+    let patches = dispatcher::remove_owner(owner);
+
+    if patches > 0 {
+        let _ = log!("Removed {} patches added by {}", patches, loaded.name());
+    }
+
+    for hook in registry::remove_owner(owner) {
+        let _ = log!("Removed hook on {:#x} installed by {}", hook.target, loaded.name());
+    }
+
+    shared
+}
+
+impl Default for ModManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `Ferrex/<name>`, created if it doesn't exist yet
+fn ferrex_dir(name: &str) -> Result<PathBuf, DynErr> {
+    let dir = std::env::current_dir()?.join("Ferrex").join(name);
+
+    if !dir.exists() {
+        fs::create_dir(&dir)?;
+    }
+
+    Ok(dir)
 }
 
 impl ModManager {
-    pub fn new() -> Result<Self, Box<dyn Error>> {
+    pub fn new() -> Self {
+        // shadow copies of the last session are never in use anymore
+        native::clear_shadow_copies();
+
+        ModManager {
+            mods: Vec::new(),
+            failed: Vec::new(),
+            skipped: Vec::new(),
+            disabled: Vec::new(),
+        }
+    }
+
+    /// loads the plugins in `Ferrex/Plugins` and runs their early init
+    fn load_plugins(&mut self) -> Result<(), DynErr> {
+        // plugins and mods both link against them, so they're loaded once, before either
+        userlibs::load();
+
+        let plugins_dir = ferrex_dir("Plugins")?;
+
+        log!("Loading Plugins")?;
+
+        let running = self.mods.len();
+
+        for file in files_with_extension(&plugins_dir, DLL_EXTENSION)? {
+            self.load(&file, |path| Ok(Box::new(NativeMod::load(path)?)))?;
+        }
+
+        self.resolve(running)?;
+        self.dispatch("on_early_init", |plugin| plugin.early_init());
+
+        Ok(())
+    }
+
+    /// loads the mods in `Ferrex/Mods`, they may depend on plugins but not the other way around
+    fn load_mods(&mut self) -> Result<(), DynErr> {
         log!("Running Bindgen")?;
 
-        bindgen::generator::run()?;
+        // mods don't need the bindings at runtime, so this isn't fatal
+        if let Err(e) = isolate(bindgen::generator::run) {
+            err!("Failed to generate bindings: {}", e.to_string())?;
+        }
+
+        let mods_dir = ferrex_dir("Mods")?;
+        let running = self.mods.len();
+
+        log!("Initializing Native Mods")?;
+
+        for file in files_with_extension(&mods_dir, DLL_EXTENSION)? {
+            if !managed::is_managed(&file) {
+                self.load(&file, |path| Ok(Box::new(NativeMod::load(path)?)))?;
+            }
+        }
+
+        log!("Initializing Managed Mods")?;
+
+        // managed assemblies are always .dll, whatever the platform
+        for file in files_with_extension(&mods_dir, "dll")? {
+            if managed::is_managed(&file) {
+                self.load(&file, |path| Ok(Box::new(ManagedMod::load(path)?)))?;
+            }
+        }
+
+        log!("Initializing Wasm Mods")?;
+
+        for file in files_with_extension(&mods_dir, "wasm")? {
+            self.load(&file, |path| Ok(Box::new(WasmMod::load(path)?)))?;
+        }
+
+        self.resolve(running)
+    }
+
+    /// orders the mods loaded after the first `running` ones, skipping those that can't run
+    fn resolve(&mut self, running: usize) -> Result<(), DynErr> {
+        let loaded = self.mods.split_off(running);
+        let (ordered, unresolved) = manifest::order(loaded, &self.mods);
+
+        for skip in unresolved.iter() {
+            warn!("Skipping {}: {}", skip.name, skip.reason)?;
+        }
+
+        // only now, so the skipped ones leave nothing behind
+        for loaded in ordered.iter() {
+            preferences::register(loaded.owner(), &loaded.manifest().id);
+            registry::register_owner(loaded.owner(), loaded.name());
+        }
+
+        self.mods.extend(ordered);
+        self.skipped.extend(unresolved);
+
+        Ok(())
+    }
+
+    /// loads a single mod, recording it as skipped or failed instead of returning its error.
+    /// it's only registered once [`Self::resolve`] has ordered it
+    fn load(
+        &mut self,
+        file: &Path,
+        load: impl FnOnce(&Path) -> Result<Box<dyn FerrexMod>, DynErr>,
+    ) -> Result<(), DynErr> {
+        log!("Loading mod from: {}", file.display())?;
+
+        let e = match isolate(|| load(file)) {
+            Ok(loaded) => {
+                self.mods.push(loaded);
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+
+        let name = file.display().to_string();
 
-        log!("Initializing Wasi Mods")?;
+        // untrusted mods, and those built against the wrong abi or with a broken manifest
+        // are rejected, not failed
+        match e.downcast_ref::<ModError>() {
+            Some(ModError::AbiMismatch { .. })
+            | Some(ModError::InvalidManifest(..))
+            | Some(ModError::Untrusted(..))
+            | Some(ModError::UnsupportedRuntime(..)) => {
+                err!("Rejecting mod: {}", e.to_string())?;
 
-        let mods_dir = std::env::current_dir()?.join("Ferrex").join("Mods");
+                self.skipped.push(Skipped {
+                    name,
+                    reason: e.to_string(),
+                });
+            }
+            _ => {
+                err!("Failed to load {}: {}", name, e.to_string())?;
 
-        if !mods_dir.exists() {
-            fs::create_dir(&mods_dir)?;
+                self.failed.push(Failed {
+                    name,
+                    reason: e.to_string(),
+                });
+            }
         }
 
-        let directory = fs::read_dir(mods_dir)?;
+        Ok(())
+    }
+
+    /// runs `f` for every enabled mod in load order, disabling the ones that fail
+    fn dispatch(&mut self, event: &str, f: impl Fn(&dyn FerrexMod) -> Result<(), DynErr>) {
+        let order = (0..self.mods.len()).collect();
+        self.dispatch_in(event, order, f);
+    }
+
+    /// like [`Self::dispatch`], but ordered by the mods' `update_order`
+    fn dispatch_frame(&mut self, event: &str, f: impl Fn(&dyn FerrexMod) -> Result<(), DynErr>) {
+        let mut order: Vec<usize> = (0..self.mods.len()).collect();
+
+        // stable, so ties keep the load order
+        order.sort_by_key(|&index| self.mods[index].manifest().update_order);
+
+        self.dispatch_in(event, order, f);
+    }
 
-        let wasm_files: Vec<DirEntry> = directory
-            .filter_map(Result::ok)
-            .filter_map(|d| {
-                d.path()
-                    .to_str()
-                    .and_then(|f| if f.ends_with(DLL_EXTENSION) { Some(d) } else { None })
+    /// runs `f` for the mods at the indices in `order`, then disables the ones that failed
+    fn dispatch_in(&mut self, event: &str, order: Vec<usize>, f: impl Fn(&dyn FerrexMod) -> Result<(), DynErr>) {
+        let mut failed: Vec<(usize, DynErr)> = order
+            .into_iter()
+            .filter_map(|index| {
+                let loaded = self.mods[index].as_ref();
+                isolate(|| f(loaded)).err().map(|e| (index, e))
             })
             .collect();
 
-        let mut plugins = Vec::new();
+        // back to front, so the remaining indices stay valid
+        failed.sort_by_key(|(index, _)| std::cmp::Reverse(*index));
 
-        for file in wasm_files {
-            log!("Loading mod from: {}", file.path().display())?;
-            let plugin = unsafe {
-                Library::new(file.path())?
-            };
+        for (index, e) in failed {
+            let disabled = self.mods.remove(index);
+            let name = disabled.name().to_string();
+            let _ = err!("{} failed in {}, disabling it: {}", name, event, e.to_string());
 
-            plugins.push(plugin);
+            self.failed.push(Failed {
+                name,
+                reason: format!("{}: {}", event, e),
+            });
+
+            // it stays loaded, but nobody should be able to call into it anymore
+            release(disabled.as_ref());
+
+            self.disabled.push(disabled);
+        }
+    }
+
+    /// unloads the mod loaded from `file`, and loads it again if the file still exists
+    fn reload(&mut self, file: &Path) -> Result<(), DynErr> {
+        if managed::is_managed(file) {
+            warn!("{} is a managed mod, restart the game to load the changes", file.display())?;
+            return Ok(());
+        }
+
+        let old = match self.mods.iter().position(|m| m.path() == file) {
+            Some(index) => Some(self.mods.remove(index)),
+            None => self
+                .disabled
+                .iter()
+                .position(|m| m.path() == file)
+                .map(|index| self.disabled.remove(index)),
+        };
+
+        if let Some(old) = old {
+            log!("Unloading {}", old.name())?;
+
+            if let Err(e) = isolate(|| old.quit()) {
+                err!("{} failed in on_quit: {}", old.name(), e.to_string())?;
+            }
+
+            let owner = old.owner();
+
+            preferences::unregister(owner);
+            let shared = release(old.as_ref());
+
+            // the game keeps calling registered internal calls, and other mods may still hold
+            // on to its services, so their code has to stay mapped
+            if internal_calls::retire(owner) || shared {
+                std::mem::forget(old);
+            }
+        }
+
+        if !file.exists() {
+            return Ok(());
+        }
+
+        let running = self.mods.len();
+        self.load(file, |path| Ok(Box::new(NativeMod::load(path)?)))?;
+
+        // checked against the running mods like at startup, it may have changed its
+        // dependencies or its game target
+        self.resolve(running)?;
+
+        if self.mods.len() == running {
+            return Ok(());
+        }
+
+        let reloaded = self.mods.pop().ok_or("Reloaded mod is missing")?;
+
+        match isolate(|| reloaded.init()) {
+            Ok(()) => {
+                log!("Reloaded {} v{}", reloaded.name(), reloaded.manifest().version)?;
+                self.mods.push(reloaded);
+            }
+            Err(e) => {
+                let name = reloaded.name().to_string();
+                err!("{} failed in on_init, disabling it: {}", name, e.to_string())?;
+
+                self.failed.push(Failed {
+                    name,
+                    reason: format!("on_init: {}", e),
+                });
+
+                release(reloaded.as_ref());
+                self.disabled.push(reloaded);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn summary(&self) -> Result<(), DynErr> {
+        log!(
+            "{} mods loaded, {} failed, {} skipped",
+            self.mods.len(),
+            self.failed.len(),
+            self.skipped.len()
+        )?;
+
+        for loaded in self.mods.iter() {
+            log!("  Loaded: {} v{}", loaded.name(), loaded.manifest().version)?;
+        }
+
+        for failed in self.failed.iter() {
+            err!("  Failed: {} ({})", failed.name, failed.reason)?;
         }
 
-        Ok(ModManager {
-            plugins
-        })
+        for skipped in self.skipped.iter() {
+            warn!("  Skipped: {} ({})", skipped.name, skipped.reason)?;
+        }
+
+        registry::log_table();
+
+        Ok(())
+    }
+}
+
+/// loads the plugins, right after Ferrex itself is initialized and before any managed code runs
+pub fn load_plugins() -> Result<(), DynErr> {
+    let mut manager = ModManager::new();
+    let result = manager.load_plugins();
+
+    // plugins may have hooked something already, so they're kept even if loading the rest failed
+    *MANAGER.lock().map_err(|_| "Mod manager lock is poisoned")? = Some(manager);
+
+    result
+}
+
+/// loads the mods, and initializes them together with the plugins
+pub fn start() -> Result<(), DynErr> {
+    let mut manager = MANAGER
+        .lock()
+        .map_err(|_| "Mod manager lock is poisoned")?
+        .take()
+        .unwrap_or_default();
+
+    if let Err(e) = manager.load_mods() {
+        err!("Failed to load mods: {}", e.to_string())?;
+    }
+
+    for loaded in manager.mods.iter() {
+        let manifest = loaded.manifest();
+
+        log!(
+            "Initializing {} v{} by {}",
+            loaded.name(),
+            manifest.version,
+            manifest.author
+        )?;
+    }
+
+    manager.dispatch("on_init", |loaded| loaded.init());
+    manager.summary()?;
+
+    if config::get().hot_reload {
+        reload::watch(std::env::current_dir()?.join("Ferrex").join("Mods"))?;
+    }
+
+    commands::listen();
+
+    *MANAGER.lock().map_err(|_| "Mod manager lock is poisoned")? = Some(manager);
+
+    Ok(())
+}
+
+/// work on the manager, queued while the same thread is already in one
+type Queued = Box<dyn FnOnce(&mut ModManager)>;
+
+thread_local! {
+    /// set while this thread holds the manager, and runs mod code with it
+    static DISPATCHING: Cell<bool> = const { Cell::new(false) };
+    /// what mods caused from inside a callback, like loading a scene, run once it returns
+    static QUEUED: RefCell<VecDeque<Queued>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// runs `f` with the manager, doing nothing before the mods are started
+///
+/// mod code runs with the manager locked, so when it leads back here, like a mod loading a
+/// scene from on_update, `f` is queued instead of locking it again, and runs after the
+/// callback that's running returns
+fn with_manager(f: impl FnOnce(&mut ModManager) + 'static) {
+    if DISPATCHING.get() {
+        QUEUED.with_borrow_mut(|queued| queued.push_back(Box::new(f)));
+        return;
+    }
+
+    let Ok(mut manager) = MANAGER.lock() else {
+        return;
+    };
+
+    let Some(manager) = manager.as_mut() else {
+        return;
+    };
+
+    DISPATCHING.set(true);
+    f(manager);
+
+    while let Some(queued) = QUEUED.with_borrow_mut(VecDeque::pop_front) {
+        queued(manager);
+    }
+
+    DISPATCHING.set(false);
+}
+
+/// runs `f` for every enabled mod
+fn dispatch(event: &'static str, f: impl Fn(&dyn FerrexMod) -> Result<(), DynErr> + 'static) {
+    with_manager(move |manager| manager.dispatch(event, f));
+}
+
+/// like [`dispatch`], for the frame callbacks
+fn dispatch_frame(event: &'static str, f: impl Fn(&dyn FerrexMod) -> Result<(), DynErr> + 'static) {
+    with_manager(move |manager| manager.dispatch_frame(event, f));
+}
+
+pub fn scene_event(event: &SceneEvent) {
+    let _ = match event {
+        SceneEvent::Loaded { scene, mode } => log!("Loaded scene {} ({}, {:?})", scene.name, scene.build_index, mode),
+        SceneEvent::Unloaded { scene } => log!("Unloaded scene {} ({})", scene.name, scene.build_index),
+        SceneEvent::ActiveChanged { previous, current } => log!(
+            "Active scene changed from {} to {} ({})",
+            previous.name,
+            current.name,
+            current.build_index
+        ),
+    };
+
+    let event = event.clone();
+    dispatch("on_scene_event", move |loaded| loaded.scene_event(&event));
+}
+
+pub fn update() {
+    dispatcher::remove_disabled();
+    commands::run_pending();
+
+    for file in reload::take_pending() {
+        with_manager(move |manager| {
+            if let Err(e) = manager.reload(&file) {
+                let _ = err!("Failed to reload {}: {}", file.display(), e.to_string());
+            }
+        });
     }
+
+    dispatch_frame("on_update", |loaded| loaded.update());
+}
+
+pub fn fixed_update() {
+    dispatch_frame("on_fixed_update", |loaded| loaded.fixed_update());
+}
+
+pub fn late_update() {
+    dispatch_frame("on_late_update", |loaded| loaded.late_update());
+}
+
+pub fn quit() {
+    dispatch("on_quit", |loaded| loaded.quit());
+    preferences::save_all();
 }
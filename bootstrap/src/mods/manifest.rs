@@ -0,0 +1,601 @@
+//! mod manifests, and the order mods get loaded in
+//!
+//! a manifest is read from a side-car `<mod>.toml` next to the mod, falling back to the one
+//! the mod embeds through its `ferrex_mod_manifest` export.
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+use serde::Deserialize;
+
+use crate::{errors::moderr::ModError, game::{self, GameInfo}};
+
+use super::{traits::FerrexMod, wasm::permissions::Capability};
+
+/// the version of Ferrex mods are checked against
+pub const FERREX_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Dependency {
+    /// any version of the mod
+    Id(String),
+    /// the mod, at `version` or newer
+    Versioned { id: String, version: String },
+}
+
+impl Dependency {
+    pub fn id(&self) -> &str {
+        match self {
+            Dependency::Id(id) => id,
+            Dependency::Versioned { id, .. } => id,
+        }
+    }
+
+    pub fn minimum_version(&self) -> Option<&str> {
+        match self {
+            Dependency::Id(_) => None,
+            Dependency::Versioned { version, .. } => Some(version),
+        }
+    }
+}
+
+/// a game a mod is made for
+#[derive(Debug, Clone, Deserialize)]
+pub struct GameTarget {
+    /// the product name, as in the game's `app.info`
+    pub name: String,
+    /// the developer, as in the game's `app.info`, any if unset
+    pub company: Option<String>,
+    /// unity versions the mod works with, `2019.4` matches every 2019.4 release. any if empty
+    #[serde(default)]
+    pub unity_versions: Vec<String>,
+}
+
+impl GameTarget {
+    pub fn matches(&self, game: &GameInfo) -> bool {
+        self.name.eq_ignore_ascii_case(&game.product)
+            && self
+                .company
+                .as_ref()
+                .is_none_or(|company| company.eq_ignore_ascii_case(&game.company))
+            && (self.unity_versions.is_empty()
+                || self
+                    .unity_versions
+                    .iter()
+                    .any(|version| game::version_matches(&game.unity_version, version)))
+    }
+}
+
+impl std::fmt::Display for GameTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        if !self.unity_versions.is_empty() {
+            write!(f, " (Unity {})", self.unity_versions.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModManifest {
+    pub id: String,
+    /// a display name, defaults to the id
+    pub name: Option<String>,
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+    /// loaded before this mod if present, ignored otherwise
+    #[serde(default)]
+    pub optional_dependencies: Vec<Dependency>,
+    /// mods that can't be loaded alongside this one
+    #[serde(default)]
+    pub incompatibilities: Vec<String>,
+    pub minimum_ferrex_version: Option<String>,
+    /// the games the mod is made for, it loads in any game if empty
+    #[serde(default)]
+    pub games: Vec<GameTarget>,
+    /// what a wasm mod may do, see [`super::wasm::permissions`]
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+    /// frame callbacks run from the lowest to the highest, ties keep the load order
+    #[serde(default)]
+    pub update_order: i32,
+}
+
+impl ModManifest {
+    /// the manifest of a mod that doesn't ship one
+    pub fn new(id: &str, version: &str, author: &str) -> Self {
+        ModManifest {
+            id: id.to_string(),
+            name: None,
+            version: version.to_string(),
+            author: author.to_string(),
+            dependencies: Vec::new(),
+            optional_dependencies: Vec::new(),
+            incompatibilities: Vec::new(),
+            minimum_ferrex_version: None,
+            games: Vec::new(),
+            capabilities: Vec::new(),
+            update_order: 0,
+        }
+    }
+
+    pub fn parse(content: &str, source: &str) -> Result<Self, ModError> {
+        let manifest: Self =
+            toml::from_str(content).map_err(|e| ModError::InvalidManifest(source.to_string(), e.to_string()))?;
+
+        // the id names the mod's files in `Ferrex/UserData`
+        if !is_valid_id(&manifest.id) {
+            return Err(ModError::InvalidManifest(
+                source.to_string(),
+                format!("id {:?} may only contain letters, digits, '.', '_' and '-'", manifest.id),
+            ));
+        }
+
+        Ok(manifest)
+    }
+
+    /// reads `<mod>.toml` next to the mod, if there is one
+    pub fn sidecar(path: &Path) -> Result<Option<Self>, ModError> {
+        let sidecar = path.with_extension("toml");
+
+        if !sidecar.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&sidecar)?;
+        Ok(Some(Self::parse(&content, &sidecar.display().to_string())?))
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+}
+
+/// whether `id` is safe to use as a file name, `.` and `..` aren't
+pub fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id != "."
+        && id != ".."
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// compares dotted versions numerically, missing parts count as 0 and anything after a `-` is ignored
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |v: &str| -> Vec<u64> {
+        v.split('-')
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.trim().parse().unwrap_or(0))
+            .collect()
+    };
+
+    let (a, b) = (parse(a), parse(b));
+    let len = a.len().max(b.len());
+
+    for i in 0..len {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// why a mod can't run in `game`, none if it can, or if the game couldn't be identified
+fn game_mismatch(manifest: &ModManifest, game: &GameInfo) -> Option<String> {
+    if manifest.games.is_empty() || !game.is_known() || manifest.games.iter().any(|target| target.matches(game)) {
+        return None;
+    }
+
+    let targets: Vec<String> = manifest.games.iter().map(|target| target.to_string()).collect();
+
+    Some(format!("made for {}, this is {}", targets.join(", "), game))
+}
+
+/// a mod that won't be loaded, and why
+#[derive(Debug, Clone)]
+pub struct Skipped {
+    pub name: String,
+    pub reason: String,
+}
+
+/// drops mods whose requirements aren't met, and sorts the rest so dependencies come first
+///
+/// `running` are mods that were started earlier, like plugins. they satisfy dependencies,
+/// but are never skipped or reordered themselves.
+pub fn order(
+    mods: Vec<Box<dyn FerrexMod>>,
+    running: &[Box<dyn FerrexMod>],
+) -> (Vec<Box<dyn FerrexMod>>, Vec<Skipped>) {
+    let mut skipped = Vec::new();
+    let mut by_id: BTreeMap<String, Box<dyn FerrexMod>> = BTreeMap::new();
+
+    let find_running = |id: &str| running.iter().find(|r| r.manifest().id == id);
+
+    for loaded in mods {
+        let manifest = loaded.manifest();
+
+        if by_id.contains_key(&manifest.id) || find_running(&manifest.id).is_some() {
+            skipped.push(Skipped {
+                name: loaded.name().to_string(),
+                reason: format!("another mod with the id {} is already loaded", manifest.id),
+            });
+            continue;
+        }
+
+        if let Some(minimum) = &manifest.minimum_ferrex_version {
+            if compare_versions(FERREX_VERSION, minimum) == Ordering::Less {
+                skipped.push(Skipped {
+                    name: loaded.name().to_string(),
+                    reason: format!("requires Ferrex {} or newer, this is {}", minimum, FERREX_VERSION),
+                });
+                continue;
+            }
+        }
+
+        if let Some(reason) = game_mismatch(manifest, game::get()) {
+            skipped.push(Skipped {
+                name: loaded.name().to_string(),
+                reason,
+            });
+            continue;
+        }
+
+        by_id.insert(manifest.id.clone(), loaded);
+    }
+
+    let incompatible: Vec<(String, String)> = by_id
+        .values()
+        .flat_map(|loaded| {
+            let manifest = loaded.manifest();
+            manifest
+                .incompatibilities
+                .iter()
+                .filter(|other| by_id.contains_key(*other) || find_running(other).is_some())
+                .map(|other| (manifest.id.clone(), other.clone()))
+                .collect::<Vec<_>>()
+        })
+        .chain(running.iter().flat_map(|r| {
+            let manifest = r.manifest();
+            manifest
+                .incompatibilities
+                .iter()
+                .filter(|other| by_id.contains_key(*other))
+                .map(|other| (other.clone(), manifest.id.clone()))
+                .collect::<Vec<_>>()
+        }))
+        .collect();
+
+    for (id, other) in incompatible {
+        if let Some(loaded) = by_id.remove(&id) {
+            skipped.push(Skipped {
+                name: loaded.name().to_string(),
+                reason: format!("it is incompatible with {}", other),
+            });
+        }
+    }
+
+    // removing a mod can leave its dependents without a dependency, so repeat until nothing changes
+    loop {
+        let missing: Vec<(String, String)> = by_id
+            .values()
+            .filter_map(|loaded| {
+                let manifest = loaded.manifest();
+                manifest.dependencies.iter().find_map(|dependency| {
+                    match by_id.get(dependency.id()).or_else(|| find_running(dependency.id())) {
+                        None => Some(format!("missing dependency {}", dependency.id())),
+                        Some(found) => match dependency.minimum_version() {
+                            Some(minimum) if compare_versions(&found.manifest().version, minimum) == Ordering::Less => {
+                                Some(format!(
+                                    "dependency {} is {}, but {} or newer is required",
+                                    dependency.id(),
+                                    found.manifest().version,
+                                    minimum
+                                ))
+                            }
+                            _ => None,
+                        },
+                    }
+                })
+                .map(|reason| (manifest.id.clone(), reason))
+            })
+            .collect();
+
+        if missing.is_empty() {
+            break;
+        }
+
+        for (id, reason) in missing {
+            if let Some(loaded) = by_id.remove(&id) {
+                skipped.push(Skipped {
+                    name: loaded.name().to_string(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    // kahn's algorithm, ids are visited in sorted order so the result is stable
+    let mut dependencies: BTreeMap<String, BTreeSet<String>> = by_id
+        .values()
+        .map(|loaded| {
+            let manifest = loaded.manifest();
+            let depends_on = manifest
+                .dependencies
+                .iter()
+                .chain(manifest.optional_dependencies.iter())
+                .map(|d| d.id().to_string())
+                .filter(|id| by_id.contains_key(id) && *id != manifest.id)
+                .collect();
+
+            (manifest.id.clone(), depends_on)
+        })
+        .collect();
+
+    let mut ordered = Vec::new();
+
+    loop {
+        let ready: Vec<String> = dependencies
+            .iter()
+            .filter(|(_, depends_on)| depends_on.is_empty())
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        if ready.is_empty() {
+            break;
+        }
+
+        for id in ready {
+            dependencies.remove(&id);
+            dependencies.values_mut().for_each(|depends_on| {
+                depends_on.remove(&id);
+            });
+
+            if let Some(loaded) = by_id.remove(&id) {
+                ordered.push(loaded);
+            }
+        }
+    }
+
+    // whatever is left is part of, or depends on, a cycle
+    for (id, depends_on) in dependencies {
+        if let Some(loaded) = by_id.remove(&id) {
+            skipped.push(Skipped {
+                name: loaded.name().to_string(),
+                reason: format!(
+                    "cyclic dependency through {}",
+                    depends_on.into_iter().collect::<Vec<_>>().join(", ")
+                ),
+            });
+        }
+    }
+
+    (ordered, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{errors::DynErr, mods::scenes::SceneEvent};
+
+    use super::*;
+
+    struct TestMod {
+        manifest: ModManifest,
+        path: PathBuf,
+    }
+
+    impl FerrexMod for TestMod {
+        fn owner(&self) -> u64 {
+            0
+        }
+
+        fn manifest(&self) -> &ModManifest {
+            &self.manifest
+        }
+
+        fn path(&self) -> &Path {
+            &self.path
+        }
+
+        fn init(&self) -> Result<(), DynErr> {
+            Ok(())
+        }
+
+        fn scene_event(&self, _event: &SceneEvent) -> Result<(), DynErr> {
+            Ok(())
+        }
+
+        fn update(&self) -> Result<(), DynErr> {
+            Ok(())
+        }
+
+        fn quit(&self) -> Result<(), DynErr> {
+            Ok(())
+        }
+    }
+
+    fn test_mod(id: &str, version: &str, dependencies: Vec<Dependency>) -> Box<dyn FerrexMod> {
+        let mut manifest = ModManifest::new(id, version, "");
+        manifest.dependencies = dependencies;
+
+        Box::new(TestMod {
+            manifest,
+            path: PathBuf::from(id),
+        })
+    }
+
+    fn incompatible_mod(id: &str, incompatibilities: &[&str]) -> Box<dyn FerrexMod> {
+        let mut manifest = ModManifest::new(id, "1.0.0", "");
+        manifest.incompatibilities = incompatibilities.iter().map(|other| other.to_string()).collect();
+
+        Box::new(TestMod {
+            manifest,
+            path: PathBuf::from(id),
+        })
+    }
+
+    fn depends(id: &str) -> Dependency {
+        Dependency::Id(id.to_string())
+    }
+
+    fn depends_at_least(id: &str, version: &str) -> Dependency {
+        Dependency::Versioned {
+            id: id.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    fn ids(mods: &[Box<dyn FerrexMod>]) -> Vec<&str> {
+        mods.iter().map(|loaded| loaded.manifest().id.as_str()).collect()
+    }
+
+    /// the reason `id` was skipped for, panics if it wasn't
+    fn reason<'a>(skipped: &'a [Skipped], id: &str) -> &'a str {
+        &skipped
+            .iter()
+            .find(|skipped| skipped.name == id)
+            .unwrap_or_else(|| panic!("{} wasn't skipped", id))
+            .reason
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert_eq!(compare_versions("1.10.0", "1.9.0"), Ordering::Greater);
+        assert_eq!(compare_versions("0.9", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("2.0.0-beta", "2.0.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn ids_are_valid_file_names() {
+        assert!(is_valid_id("my-mod_2.0"));
+        assert!(!is_valid_id(""));
+        assert!(!is_valid_id("."));
+        assert!(!is_valid_id(".."));
+        assert!(!is_valid_id("../other"));
+        assert!(!is_valid_id("my mod"));
+
+        assert!(ModManifest::parse("id = \"my-mod\"\nversion = \"1.0.0\"", "test").is_ok());
+        assert!(ModManifest::parse("id = \"../../mod\"\nversion = \"1.0.0\"", "test").is_err());
+    }
+
+    #[test]
+    fn dependencies_come_first() {
+        let mods = vec![
+            test_mod("c", "1.0.0", vec![depends("b")]),
+            test_mod("b", "1.0.0", vec![depends("a")]),
+            test_mod("a", "1.0.0", vec![]),
+        ];
+
+        let (ordered, skipped) = order(mods, &[]);
+
+        assert_eq!(ids(&ordered), ["a", "b", "c"]);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn missing_dependencies_are_skipped_with_their_dependents() {
+        let mods = vec![
+            test_mod("a", "1.0.0", vec![depends("missing")]),
+            test_mod("b", "1.0.0", vec![depends("a")]),
+            test_mod("c", "1.0.0", vec![]),
+        ];
+
+        let (ordered, skipped) = order(mods, &[]);
+
+        assert_eq!(ids(&ordered), ["c"]);
+        assert_eq!(reason(&skipped, "a"), "missing dependency missing");
+        assert_eq!(reason(&skipped, "b"), "missing dependency a");
+    }
+
+    #[test]
+    fn too_old_dependencies_are_skipped() {
+        let mods = vec![
+            test_mod("a", "1.5.0", vec![]),
+            test_mod("b", "1.0.0", vec![depends_at_least("a", "2.0")]),
+            test_mod("c", "1.0.0", vec![depends_at_least("a", "1.5")]),
+        ];
+
+        let (ordered, skipped) = order(mods, &[]);
+
+        assert_eq!(ids(&ordered), ["a", "c"]);
+        assert_eq!(reason(&skipped, "b"), "dependency a is 1.5.0, but 2.0 or newer is required");
+    }
+
+    #[test]
+    fn cycles_are_skipped() {
+        let mods = vec![
+            test_mod("a", "1.0.0", vec![depends("b")]),
+            test_mod("b", "1.0.0", vec![depends("a")]),
+            test_mod("c", "1.0.0", vec![depends("a")]),
+            test_mod("d", "1.0.0", vec![]),
+        ];
+
+        let (ordered, skipped) = order(mods, &[]);
+
+        assert_eq!(ids(&ordered), ["d"]);
+        assert_eq!(reason(&skipped, "a"), "cyclic dependency through b");
+        assert_eq!(reason(&skipped, "b"), "cyclic dependency through a");
+        assert_eq!(reason(&skipped, "c"), "cyclic dependency through a");
+    }
+
+    #[test]
+    fn running_plugins_satisfy_dependencies() {
+        let running = vec![test_mod("plugin", "2.0.0", vec![])];
+        let mods = vec![test_mod("a", "1.0.0", vec![depends_at_least("plugin", "2.0")])];
+
+        let (ordered, skipped) = order(mods, &running);
+
+        assert_eq!(ids(&ordered), ["a"]);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn mods_incompatible_with_a_running_plugin_are_skipped() {
+        let running = vec![incompatible_mod("plugin", &["a"])];
+        let mods = vec![
+            test_mod("a", "1.0.0", vec![]),
+            incompatible_mod("b", &["plugin"]),
+            test_mod("c", "1.0.0", vec![depends("a")]),
+        ];
+
+        let (ordered, skipped) = order(mods, &running);
+
+        assert!(ordered.is_empty());
+        assert_eq!(reason(&skipped, "a"), "it is incompatible with plugin");
+        assert_eq!(reason(&skipped, "b"), "it is incompatible with plugin");
+        assert_eq!(reason(&skipped, "c"), "missing dependency a");
+    }
+
+    #[test]
+    fn duplicate_ids_are_skipped() {
+        let running = vec![test_mod("plugin", "1.0.0", vec![])];
+        let mods = vec![
+            test_mod("a", "1.0.0", vec![]),
+            test_mod("a", "2.0.0", vec![]),
+            test_mod("plugin", "1.0.0", vec![]),
+        ];
+
+        let (ordered, skipped) = order(mods, &running);
+
+        assert_eq!(ids(&ordered), ["a"]);
+        assert_eq!(ordered[0].manifest().version, "1.0.0");
+        assert_eq!(skipped.len(), 2);
+    }
+}
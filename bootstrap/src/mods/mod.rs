@@ -1 +1,14 @@
-pub mod manager;
\ No newline at end of file
+pub mod abi;
+pub mod bus;
+pub mod internal_calls;
+pub mod managed;
+pub mod manager;
+pub mod manifest;
+pub mod native;
+pub mod reload;
+pub mod scenes;
+pub mod services;
+pub mod traits;
+pub mod trust;
+pub mod userlibs;
+pub mod wasm;
\ No newline at end of file
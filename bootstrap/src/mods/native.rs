@@ -0,0 +1,247 @@
+//! a loaded native mod, and the callbacks it exports
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use libloading::Library;
+
+use crate::errors::{moderr::ModError, DynErr};
+
+use super::{
+    abi::{
+        self, FerrexHost, FerrexModInfo, FerrexSceneEvent, ModInfoFn, ModManifestFn, OnEarlyInitFn,
+        OnFixedUpdateFn, OnInitFn, OnLateUpdateFn, OnQuitFn, OnSceneEventFn, OnSceneLoadedFn,
+        OnUpdateFn, FERREX_ABI_VERSION,
+    },
+    manifest::ModManifest,
+    scenes::{Scene, SceneEvent},
+    traits::{self, FerrexMod},
+    trust, userlibs,
+};
+
+#[derive(Debug)]
+pub struct NativeMod {
+    pub manifest: ModManifest,
+    /// the library in `Ferrex/Mods`, the one actually loaded is a shadow copy of it
+    pub path: PathBuf,
+    owner: u64,
+    /// boxed, since the mod may hold on to the pointer it got in `on_init`
+    host: Box<FerrexHost>,
+
+    on_early_init: Option<OnEarlyInitFn>,
+    on_init: Option<OnInitFn>,
+    on_scene_loaded: Option<OnSceneLoadedFn>,
+    on_scene_event: Option<OnSceneEventFn>,
+    on_fixed_update: Option<OnFixedUpdateFn>,
+    on_update: Option<OnUpdateFn>,
+    on_late_update: Option<OnLateUpdateFn>,
+    on_quit: Option<OnQuitFn>,
+
+    /// kept last, so the callbacks above never outlive the library
+    _library: Library,
+}
+
+fn read_str(ptr: *const c_char) -> String {
+    match ptr.is_null() {
+        true => String::new(),
+        false => unsafe { CStr::from_ptr(ptr) }.to_string_lossy().to_string(),
+    }
+}
+
+fn shadow_dir() -> PathBuf {
+    PathBuf::from("Ferrex").join("Temp").join("Mods")
+}
+
+/// removes the shadow copies of the last session
+pub fn clear_shadow_copies() {
+    let _ = fs::remove_dir_all(shadow_dir());
+}
+
+/// copies the library somewhere else before loading it, so the original can be
+/// overwritten by a new build while the game is running
+fn shadow_copy(path: &Path, owner: u64) -> Result<PathBuf, ModError> {
+    let dir = shadow_dir();
+    fs::create_dir_all(&dir)?;
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    let shadow = dir.join(format!("{}-{}.{}", stem, owner, extension));
+
+    fs::copy(path, &shadow)?;
+
+    Ok(shadow)
+}
+
+/// looks up an optional export, copying the function pointer out of the symbol
+unsafe fn optional<T: Copy>(library: &Library, symbol: &[u8]) -> Option<T> {
+    library.get::<T>(symbol).ok().map(|s| *s)
+}
+
+impl NativeMod {
+    /// loads the library at `path` and checks that it speaks our abi
+    pub fn load(path: &Path) -> Result<Self, ModError> {
+        let file_name = path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        userlibs::report_dependencies(path);
+
+        let owner = traits::next_owner();
+        let shadow = shadow_copy(path, owner)?;
+
+        // the original may be replaced at any time, so the copy is what has to be trusted
+        trust::verify_copy(&shadow, path)?;
+
+        unsafe {
+            let library = Library::new(shadow)?;
+
+            let mod_info = library
+                .get::<ModInfoFn>(abi::MOD_INFO_SYMBOL)
+                .map_err(|_| ModError::MissingModInfo(file_name.clone()))?;
+
+            let info: *const FerrexModInfo = mod_info();
+            if info.is_null() {
+                return Err(ModError::MissingModInfo(file_name));
+            }
+
+            let info = &*info;
+            if info.abi_version != FERREX_ABI_VERSION {
+                return Err(ModError::AbiMismatch {
+                    name: file_name,
+                    found: info.abi_version,
+                    expected: FERREX_ABI_VERSION,
+                });
+            }
+
+            let name = match read_str(info.name) {
+                name if name.is_empty() => file_name.clone(),
+                name => name,
+            };
+
+            let manifest = match ModManifest::sidecar(path)? {
+                Some(sidecar) => sidecar,
+                None => match optional::<ModManifestFn>(&library, abi::MOD_MANIFEST_SYMBOL) {
+                    Some(embedded) => ModManifest::parse(&read_str(embedded()), &file_name)?,
+                    None => ModManifest::new(&name, &read_str(info.version), &read_str(info.author)),
+                },
+            };
+
+            Ok(NativeMod {
+                manifest,
+                path: path.to_path_buf(),
+                owner,
+                host: Box::new(abi::host(owner)),
+                on_early_init: optional(&library, abi::ON_EARLY_INIT_SYMBOL),
+                on_init: optional(&library, abi::ON_INIT_SYMBOL),
+                on_scene_loaded: optional(&library, abi::ON_SCENE_LOADED_SYMBOL),
+                on_scene_event: optional(&library, abi::ON_SCENE_EVENT_SYMBOL),
+                on_fixed_update: optional(&library, abi::ON_FIXED_UPDATE_SYMBOL),
+                on_update: optional(&library, abi::ON_UPDATE_SYMBOL),
+                on_late_update: optional(&library, abi::ON_LATE_UPDATE_SYMBOL),
+                on_quit: optional(&library, abi::ON_QUIT_SYMBOL),
+                _library: library,
+            })
+        }
+    }
+}
+
+impl FerrexMod for NativeMod {
+    fn owner(&self) -> u64 {
+        self.owner
+    }
+
+    fn manifest(&self) -> &ModManifest {
+        &self.manifest
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn early_init(&self) -> Result<(), DynErr> {
+        if let Some(on_early_init) = self.on_early_init {
+            unsafe { on_early_init(self.host.as_ref()) }
+        }
+
+        Ok(())
+    }
+
+    fn init(&self) -> Result<(), DynErr> {
+        if let Some(on_init) = self.on_init {
+            unsafe { on_init(self.host.as_ref()) }
+        }
+
+        Ok(())
+    }
+
+    fn scene_event(&self, event: &SceneEvent) -> Result<(), DynErr> {
+        if let (Some(on_scene_loaded), SceneEvent::Loaded { scene, .. }) = (self.on_scene_loaded, event) {
+            let name = CString::new(scene.name.as_str())?;
+            unsafe { on_scene_loaded(scene.build_index, name.as_ptr()) }
+        }
+
+        let Some(on_scene_event) = self.on_scene_event else {
+            return Ok(());
+        };
+
+        let none = Scene::none();
+
+        let (kind, mode, scene, previous) = match event {
+            SceneEvent::Loaded { scene, mode } => (abi::SCENE_LOADED, *mode as u32, scene, &none),
+            SceneEvent::Unloaded { scene } => (abi::SCENE_UNLOADED, 0, scene, &none),
+            SceneEvent::ActiveChanged { previous, current } => (abi::ACTIVE_SCENE_CHANGED, 0, current, previous),
+        };
+
+        let name = CString::new(scene.name.as_str())?;
+        let previous_name = CString::new(previous.name.as_str())?;
+
+        let event = FerrexSceneEvent {
+            kind,
+            mode,
+            name: name.as_ptr(),
+            build_index: scene.build_index,
+            previous_name: previous_name.as_ptr(),
+            previous_build_index: previous.build_index,
+        };
+
+        unsafe { on_scene_event(&event) }
+
+        Ok(())
+    }
+
+    fn fixed_update(&self) -> Result<(), DynErr> {
+        if let Some(on_fixed_update) = self.on_fixed_update {
+            unsafe { on_fixed_update() }
+        }
+
+        Ok(())
+    }
+
+    fn update(&self) -> Result<(), DynErr> {
+        if let Some(on_update) = self.on_update {
+            unsafe { on_update() }
+        }
+
+        Ok(())
+    }
+
+    fn late_update(&self) -> Result<(), DynErr> {
+        if let Some(on_late_update) = self.on_late_update {
+            unsafe { on_late_update() }
+        }
+
+        Ok(())
+    }
+
+    fn quit(&self) -> Result<(), DynErr> {
+        if let Some(on_quit) = self.on_quit {
+            unsafe { on_quit() }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+//! watches `Ferrex/Mods` for rebuilt native mods
+//!
+//! the watcher only queues changed files, the reload itself happens on the main thread
+//! the next time the mods are updated
+
+use std::{
+    collections::HashMap,
+    env::consts::DLL_EXTENSION,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use lazy_static::lazy_static;
+
+use crate::{errors::DynErr, log};
+
+lazy_static! {
+    static ref PENDING: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+}
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// modification time and size, compared between polls
+type Stamp = (SystemTime, u64);
+
+fn stamps(dir: &Path) -> HashMap<PathBuf, Stamp> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return HashMap::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == DLL_EXTENSION))
+        .filter_map(|p| {
+            let metadata = fs::metadata(&p).ok()?;
+            Some((p, (metadata.modified().ok()?, metadata.len())))
+        })
+        .collect()
+}
+
+fn queue(path: PathBuf) {
+    if let Ok(mut pending) = PENDING.lock() {
+        if !pending.contains(&path) {
+            pending.push(path);
+        }
+    }
+}
+
+/// starts polling `dir` on a background thread
+pub fn watch(dir: PathBuf) -> Result<(), DynErr> {
+    log!("Watching {} for changes", dir.display())?;
+
+    thread::Builder::new()
+        .name("ferrex-hot-reload".to_string())
+        .spawn(move || {
+            let mut known = stamps(&dir);
+            // files that changed in the last poll, only queued once they stop changing,
+            // so a library that is still being written isn't loaded half way
+            let mut changing: HashMap<PathBuf, Stamp> = HashMap::new();
+
+            loop {
+                thread::sleep(POLL_INTERVAL);
+
+                let current = stamps(&dir);
+
+                for (path, stamp) in current.iter() {
+                    if known.get(path) == Some(stamp) {
+                        continue;
+                    }
+
+                    if changing.get(path) == Some(stamp) {
+                        changing.remove(path);
+                        known.insert(path.clone(), *stamp);
+                        queue(path.clone());
+                    } else {
+                        changing.insert(path.clone(), *stamp);
+                    }
+                }
+
+                let removed: Vec<PathBuf> = known
+                    .keys()
+                    .filter(|p| !current.contains_key(*p))
+                    .cloned()
+                    .collect();
+
+                for path in removed {
+                    known.remove(&path);
+                    queue(path);
+                }
+
+                changing.retain(|p, _| current.contains_key(p));
+            }
+        })?;
+
+    Ok(())
+}
+
+/// the files that changed since the last call
+pub fn take_pending() -> Vec<PathBuf> {
+    match PENDING.lock() {
+        Ok(mut pending) => std::mem::take(&mut *pending),
+        Err(_) => Vec::new(),
+    }
+}
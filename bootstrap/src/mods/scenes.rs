@@ -0,0 +1,172 @@
+//! scene events, read off the calls unity makes into `SceneManager` when scenes change
+//!
+//! unity invokes `Internal_SceneLoaded`, `Internal_SceneUnloaded` and `Internal_ActiveSceneChanged`
+//! through runtime_invoke on both runtimes, so the invoke hook sees every one of them.
+
+use std::{ffi::c_void, sync::OnceLock};
+
+use unity_rs::{
+    common::{class::UnityClass, object::UnityObject, string::UnityString},
+    runtime::FerrexRuntime,
+};
+
+use crate::{core, errors::DynErr, log, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LoadSceneMode {
+    /// the other scenes were unloaded
+    Single = 0,
+    /// loaded next to the scenes that were already loaded
+    Additive = 1,
+}
+
+#[derive(Debug, Clone)]
+pub struct Scene {
+    /// empty for an invalid scene, like the previous one when the first scene becomes active
+    pub name: String,
+    /// -1 for scenes that aren't in the build settings
+    pub build_index: i32,
+}
+
+impl Scene {
+    pub fn none() -> Self {
+        Scene {
+            name: String::new(),
+            build_index: -1,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SceneEvent {
+    Loaded { scene: Scene, mode: LoadSceneMode },
+    Unloaded { scene: Scene },
+    ActiveChanged { previous: Scene, current: Scene },
+}
+
+/// the methods unity calls, and what's needed to read a `Scene`
+#[derive(Debug)]
+struct SceneMethods {
+    loaded: usize,
+    unloaded: usize,
+    active_changed: usize,
+    scene: UnityClass,
+}
+
+/// none if this unity version has no `SceneManager`
+static METHODS: OnceLock<Option<SceneMethods>> = OnceLock::new();
+
+fn lookup(runtime: &FerrexRuntime) -> Result<SceneMethods, DynErr> {
+    let namespace = "UnityEngine.SceneManagement";
+    let manager = UnityClass::find(None, namespace, "SceneManager", runtime)?;
+    let scene = UnityClass::find(None, namespace, "Scene", runtime)?;
+
+    let method = |name: &str, args: i32| -> Result<usize, DynErr> {
+        Ok(manager.get_method(name, args, runtime)?.inner as usize)
+    };
+
+    Ok(SceneMethods {
+        loaded: method("Internal_SceneLoaded", 2)?,
+        unloaded: method("Internal_SceneUnloaded", 1)?,
+        active_changed: method("Internal_ActiveSceneChanged", 2)?,
+        scene,
+    })
+}
+
+/// looks up the scene callbacks, so calls to them can be recognized by their method pointer
+pub fn init() {
+    METHODS.get_or_init(|| {
+        let methods = core::get_runtime().and_then(lookup);
+
+        match methods {
+            Ok(methods) => {
+                let _ = log!("Watching scene changes");
+                Some(methods)
+            }
+            Err(e) => {
+                let _ = warn!("Scene events are unavailable: {}", e.to_string());
+                None
+            }
+        }
+    });
+}
+
+impl SceneMethods {
+    /// calls a property getter on the `Scene` at `scene`, value types are passed unboxed
+    fn property(&self, scene: *mut c_void, name: &str, runtime: &FerrexRuntime) -> Result<Option<UnityObject>, DynErr> {
+        let property = self.scene.get_property(name, runtime)?;
+
+        Ok(runtime
+            .get_property_get_method(&property)?
+            .try_invoke(Some(&UnityObject { inner: scene }), None, runtime)?)
+    }
+
+    /// reads the `Scene` struct `scene` points to
+    fn scene(&self, scene: *mut c_void, runtime: &FerrexRuntime) -> Result<Scene, DynErr> {
+        // a scene is just its handle, 0 is no scene at all
+        if scene.is_null() || unsafe { *scene.cast::<i32>() } == 0 {
+            return Ok(Scene::none());
+        }
+
+        let name = match self.property(scene, "name", runtime)? {
+            Some(name) => UnityString { inner: name.inner }.to_string(runtime)?,
+            None => String::new(),
+        };
+
+        let build_index = match self.property(scene, "buildIndex", runtime)? {
+            Some(index) => unsafe { *runtime.unbox_object(&index)?.inner.cast::<i32>() },
+            None => -1,
+        };
+
+        Ok(Scene { name, build_index })
+    }
+}
+
+/// reads a single argument pointer
+unsafe fn param(params: *mut *mut c_void, index: usize) -> *mut c_void {
+    match params.is_null() {
+        true => std::ptr::null_mut(),
+        false => *params.add(index),
+    }
+}
+
+/// the event for a call to `method`, none if it isn't one of the scene callbacks
+pub fn event(method: *mut c_void, params: *mut *mut c_void) -> Result<Option<SceneEvent>, DynErr> {
+    let Some(Some(methods)) = METHODS.get() else {
+        return Ok(None);
+    };
+
+    let method = method as usize;
+
+    if method != methods.loaded && method != methods.unloaded && method != methods.active_changed {
+        return Ok(None);
+    }
+
+    let runtime = core::get_runtime()?;
+
+    let event = unsafe {
+        if method == methods.loaded {
+            let mode = param(params, 1);
+
+            SceneEvent::Loaded {
+                scene: methods.scene(param(params, 0), runtime)?,
+                mode: match !mode.is_null() && *mode.cast::<i32>() == 1 {
+                    true => LoadSceneMode::Additive,
+                    false => LoadSceneMode::Single,
+                },
+            }
+        } else if method == methods.unloaded {
+            SceneEvent::Unloaded {
+                scene: methods.scene(param(params, 0), runtime)?,
+            }
+        } else {
+            SceneEvent::ActiveChanged {
+                previous: methods.scene(param(params, 0), runtime)?,
+                current: methods.scene(param(params, 1), runtime)?,
+            }
+        }
+    };
+
+    Ok(Some(event))
+}
@@ -0,0 +1,173 @@
+//! services mods publish for each other
+//!
+//! a service is a name, a version and a pointer. for C services it points to a table of
+//! functions, Rust mods may instead share a `Box<dyn Trait>`, which only works between mods
+//! built with the same compiler, so the two kinds are never mixed up.
+//!
+//! whenever a service is published or removed, its name is sent on the bus, so mods can look
+//! it up again after the mod providing it was reloaded.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::c_void,
+    sync::Mutex,
+};
+
+use lazy_static::lazy_static;
+
+use crate::{errors::DynErr, log};
+
+use super::{bus, manifest::compare_versions};
+
+pub const SERVICE_PUBLISHED_TOPIC: &str = "ferrex.service.published";
+pub const SERVICE_REMOVED_TOPIC: &str = "ferrex.service.removed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ServiceKind {
+    /// a table of C functions
+    C = 0,
+    /// a pointer to a Rust trait object
+    Rust = 1,
+}
+
+impl TryFrom<u32> for ServiceKind {
+    type Error = String;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ServiceKind::C),
+            1 => Ok(ServiceKind::Rust),
+            _ => Err(format!("Unknown service kind {}", value)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Service {
+    owner: u64,
+    version: String,
+    kind: ServiceKind,
+    /// has to stay valid for as long as the owner is loaded
+    pointer: usize,
+}
+
+lazy_static! {
+    static ref SERVICES: Mutex<HashMap<String, Service>> = Mutex::new(HashMap::new());
+    /// owners whose services another mod looked up, and may still be holding on to
+    static ref SHARED: Mutex<HashSet<u64>> = Mutex::new(HashSet::new());
+}
+
+fn major(version: &str) -> &str {
+    version.split(['.', '-']).next().unwrap_or_default()
+}
+
+/// same major version, and at least the requested one
+fn compatible(found: &str, requested: &str) -> bool {
+    major(found) == major(requested) && compare_versions(found, requested).is_ge()
+}
+
+/// publishes a service, replacing an older one by the same mod
+pub fn publish(
+    owner: u64,
+    name: &str,
+    version: &str,
+    kind: ServiceKind,
+    pointer: *const c_void,
+) -> Result<(), DynErr> {
+    if pointer.is_null() {
+        return Err(format!("Service {} is a null pointer", name).into());
+    }
+
+    {
+        let mut services = SERVICES.lock().unwrap_or_else(|e| e.into_inner());
+
+        if services.get(name).is_some_and(|s| s.owner != owner) {
+            return Err(format!("Service {} is already published by another mod", name).into());
+        }
+
+        services.insert(
+            name.to_string(),
+            Service {
+                owner,
+                version: version.to_string(),
+                kind,
+                pointer: pointer as usize,
+            },
+        );
+    }
+
+    log!("Published service {} v{}", name, version)?;
+    bus::publish(SERVICE_PUBLISHED_TOPIC, name.as_bytes());
+
+    Ok(())
+}
+
+/// looks up a service compatible with `version`, none if it isn't published
+///
+/// a service that exists but doesn't fit is an error, since that's most likely a mistake
+pub fn lookup(owner: u64, name: &str, version: &str, kind: ServiceKind) -> Result<Option<*const c_void>, DynErr> {
+    let services = SERVICES.lock().unwrap_or_else(|e| e.into_inner());
+
+    let Some(service) = services.get(name) else {
+        return Ok(None);
+    };
+
+    if service.kind != kind {
+        return Err(format!("Service {} is a {:?} service, not a {:?} one", name, service.kind, kind).into());
+    }
+
+    if !compatible(&service.version, version) {
+        return Err(format!("Service {} is v{}, which isn't compatible with v{}", name, service.version, version).into());
+    }
+
+    if service.owner != owner {
+        SHARED.lock().unwrap_or_else(|e| e.into_inner()).insert(service.owner);
+    }
+
+    Ok(Some(service.pointer as *const c_void))
+}
+
+/// removes a service, only the mod that published it may do so
+pub fn withdraw(owner: u64, name: &str) -> Result<(), DynErr> {
+    {
+        let mut services = SERVICES.lock().unwrap_or_else(|e| e.into_inner());
+
+        match services.get(name) {
+            Some(service) if service.owner == owner => {
+                services.remove(name);
+            }
+            Some(_) => return Err(format!("Service {} is published by another mod", name).into()),
+            None => return Err(format!("Service {} is not published", name).into()),
+        }
+    }
+
+    bus::publish(SERVICE_REMOVED_TOPIC, name.as_bytes());
+
+    Ok(())
+}
+
+/// removes every service of an unloaded or disabled mod, returns whether another mod used one,
+/// in which case its library has to stay mapped
+pub fn remove_owner(owner: u64) -> bool {
+    let removed: Vec<String> = {
+        let mut services = SERVICES.lock().unwrap_or_else(|e| e.into_inner());
+        let names: Vec<String> = services
+            .iter()
+            .filter(|(_, s)| s.owner == owner)
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        names.iter().for_each(|name| {
+            services.remove(name);
+        });
+        names
+    };
+
+    for name in removed.iter() {
+        let _ = log!("Removed service {}", name);
+        bus::publish(SERVICE_REMOVED_TOPIC, name.as_bytes());
+    }
+
+    SHARED.lock().unwrap_or_else(|e| e.into_inner()).remove(&owner)
+}
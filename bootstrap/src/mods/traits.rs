@@ -0,0 +1,48 @@
+//! what the manager needs from a mod, whatever it was built with
+
+use std::{
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::errors::DynErr;
+
+use super::{manifest::ModManifest, scenes::SceneEvent};
+
+static NEXT_OWNER: AtomicU64 = AtomicU64::new(1);
+
+/// a fresh id for a mod being loaded, hooks and the like are tracked by it
+pub fn next_owner() -> u64 {
+    NEXT_OWNER.fetch_add(1, Ordering::Relaxed)
+}
+
+pub trait FerrexMod: Send {
+    /// the id everything this mod sets up is registered under, unique per load
+    fn owner(&self) -> u64;
+    fn manifest(&self) -> &ModManifest;
+    fn path(&self) -> &Path;
+
+    fn name(&self) -> &str {
+        self.manifest().display_name()
+    }
+
+    /// only called for plugins, before the runtime has executed any managed code
+    fn early_init(&self) -> Result<(), DynErr> {
+        Ok(())
+    }
+
+    fn init(&self) -> Result<(), DynErr>;
+    fn scene_event(&self, event: &SceneEvent) -> Result<(), DynErr>;
+    fn fixed_update(&self) -> Result<(), DynErr> {
+        Ok(())
+    }
+
+    fn update(&self) -> Result<(), DynErr>;
+
+    /// there's no OnGUI counterpart, see [`player_loop`](crate::hooking::player_loop)
+    fn late_update(&self) -> Result<(), DynErr> {
+        Ok(())
+    }
+
+    fn quit(&self) -> Result<(), DynErr>;
+}
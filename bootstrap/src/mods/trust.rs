@@ -0,0 +1,171 @@
+//! the optional trust policy in `Ferrex/Trust.toml`, checked before a native library is loaded
+//!
+//! ```toml
+//! # sha-256 hashes of libraries that may be loaded
+//! hashes = ["9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"]
+//! # ed25519 public keys, a library signed by one of them may be loaded.
+//! # the signature is read from `<library>.sig`, hex encoded
+//! signers = ["d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"]
+//! ```
+//!
+//! without the file every library is trusted. a file that can't be read trusts nothing.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::{config, console::commands, err, errors::moderr::ModError, log, warn};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Policy {
+    hashes: Vec<String>,
+    signers: Vec<String>,
+}
+
+/// none if there is no policy file
+static POLICY: OnceLock<Option<Policy>> = OnceLock::new();
+
+fn path() -> PathBuf {
+    PathBuf::from("Ferrex").join("Trust.toml")
+}
+
+fn load() -> Option<Policy> {
+    let content = match fs::read_to_string(path()) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let _ = log!("No trust policy found, every mod is trusted");
+            return None;
+        }
+        Err(e) => {
+            let _ = err!("Failed to read {}, no mod is trusted: {}", path().display(), e.to_string());
+            return Some(Policy::default());
+        }
+    };
+
+    match toml::from_str::<Policy>(&content) {
+        Ok(policy) => {
+            let _ = log!(
+                "Loaded trust policy with {} hashes and {} signers",
+                policy.hashes.len(),
+                policy.signers.len()
+            );
+            Some(policy)
+        }
+        Err(e) => {
+            let _ = err!("Failed to parse {}, no mod is trusted: {}", path().display(), e.to_string());
+            Some(Policy::default())
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    let hex = hex.trim();
+
+    if hex.len() != N * 2 || !hex.is_ascii() {
+        return None;
+    }
+
+    let mut bytes = [0; N];
+
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+
+    Some(bytes)
+}
+
+/// the signer whose key verifies the signature next to `path`
+fn signer<'a>(policy: &'a Policy, path: &Path, data: &[u8]) -> Result<Option<&'a str>, String> {
+    let mut signature_path = path.as_os_str().to_owned();
+    signature_path.push(".sig");
+
+    let signature = match fs::read_to_string(&signature_path) {
+        Ok(signature) => signature,
+        Err(_) => return Ok(None),
+    };
+
+    let signature = from_hex::<64>(&signature)
+        .map(|bytes| Signature::from_bytes(&bytes))
+        .ok_or("its signature file is malformed")?;
+
+    for signer in policy.signers.iter() {
+        let Some(key) = from_hex::<32>(signer).and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok()) else {
+            let _ = warn!("Ignoring malformed signer key {} in the trust policy", signer);
+            continue;
+        };
+
+        if key.verify_strict(data, &signature).is_ok() {
+            return Ok(Some(signer));
+        }
+    }
+
+    Err("it isn't signed by a trusted key".to_string())
+}
+
+/// asks on the console, anything but yes is a no
+fn confirm(name: &str, hash: &str) -> bool {
+    print!("{} (sha256 {}) is not trusted, load it anyway? [y/N] ", name, hash);
+
+    if io::stdout().flush().is_err() {
+        return false;
+    }
+
+    match commands::read_line() {
+        Some(answer) => matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"),
+        None => false,
+    }
+}
+
+/// checks the library at `path` against the trust policy, logging the decision
+pub fn verify(path: &Path) -> Result<(), ModError> {
+    verify_copy(path, path)
+}
+
+/// like [`verify`], for a copy of the library at `original`, which is what's actually loaded.
+/// the name and signature are taken from the original.
+pub fn verify_copy(copy: &Path, original: &Path) -> Result<(), ModError> {
+    let name = original.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    let Some(policy) = POLICY.get_or_init(load) else {
+        let _ = log!("Trusting {}: there is no trust policy", name);
+        return Ok(());
+    };
+
+    let data = fs::read(copy)?;
+    let hash = to_hex(&Sha256::digest(&data));
+
+    if policy.hashes.iter().any(|h| h.trim().eq_ignore_ascii_case(&hash)) {
+        let _ = log!("Trusting {}: its hash is allowed", name);
+        return Ok(());
+    }
+
+    let reason = match signer(policy, original, &data) {
+        Ok(Some(signer)) => {
+            let _ = log!("Trusting {}: it is signed by {}", name, signer);
+            return Ok(());
+        }
+        Ok(None) => format!("its hash {} isn't allowed and it isn't signed", hash),
+        Err(reason) => format!("its hash {} isn't allowed and {}", hash, reason),
+    };
+
+    if config::get().confirm_untrusted_mods && confirm(&name, &hash) {
+        let _ = warn!("Loading untrusted {}, confirmed by the user: {}", name, reason);
+        return Ok(());
+    }
+
+    let _ = warn!("Not loading untrusted {}: {}", name, reason);
+
+    Err(ModError::Untrusted(name, reason))
+}
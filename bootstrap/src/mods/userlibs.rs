@@ -0,0 +1,189 @@
+//! shared native libraries in `Ferrex/UserLibs`
+//!
+//! they're loaded globally before any plugin or mod, so mods can link against them
+//! without them being mistaken for mods themselves
+
+use std::{
+    collections::HashMap,
+    env::{self, consts::DLL_EXTENSION},
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use object::{
+    read::elf::{Dyn, ElfFile, FileHeader},
+    elf::DT_NEEDED,
+    Object,
+};
+use unity_rs::libs::{self, LibError};
+
+use crate::{err, errors::DynErr, log};
+
+use super::trust;
+
+/// the file names of the loaded libraries
+static LOADED: OnceLock<Vec<String>> = OnceLock::new();
+
+fn user_libs_dir() -> PathBuf {
+    PathBuf::from("Ferrex").join("UserLibs")
+}
+
+/// lets windows find the libraries' own dependencies in the same folder
+#[cfg(windows)]
+fn add_to_search_path(dir: &Path) -> Result<(), DynErr> {
+    let mut paths = vec![dir.to_path_buf()];
+    paths.extend(env::split_paths(&env::var_os("PATH").unwrap_or_default()));
+
+    env::set_var("PATH", env::join_paths(paths)?);
+
+    Ok(())
+}
+
+/// the loader only reads `LD_LIBRARY_PATH` at startup, and setting it races with other
+/// threads reading the environment. libraries loaded here are still found by mods, since
+/// the loader reuses an already loaded library of the same name
+#[cfg(not(windows))]
+fn add_to_search_path(_dir: &Path) -> Result<(), DynErr> {
+    Ok(())
+}
+
+fn load_all() -> Result<Vec<String>, DynErr> {
+    let dir = env::current_dir()?.join(user_libs_dir());
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir)?;
+    }
+
+    add_to_search_path(&dir)?;
+
+    let mut pending: Vec<PathBuf> = fs::read_dir(&dir)?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == DLL_EXTENSION))
+        .collect();
+
+    pending.sort();
+
+    // user libs run code as soon as they're loaded, just like mods
+    pending.retain(|path| trust::verify(path).is_ok());
+
+    if pending.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    log!("Loading User Libs")?;
+
+    let mut loaded = Vec::new();
+    // the last attempt's error, the earlier ones may just be missing a dependency
+    let mut errors: HashMap<PathBuf, LibError> = HashMap::new();
+
+    // libraries may depend on each other, so retry the ones that failed until nothing changes
+    loop {
+        let before = pending.len();
+
+        pending.retain(|path| match libs::load_lib(path) {
+            Ok(lib) => {
+                let _ = log!("Loaded user lib {}", lib.name);
+                loaded.push(lib.name);
+                false
+            }
+            Err(e) => {
+                errors.insert(path.clone(), e);
+                true
+            }
+        });
+
+        if pending.is_empty() || pending.len() == before {
+            break;
+        }
+    }
+
+    for path in pending {
+        match errors.get(&path) {
+            Some(e) => err!("Failed to load user lib {}: {}", path.display(), e.to_string())?,
+            None => err!("Failed to load user lib {}", path.display())?,
+        }
+    }
+
+    Ok(loaded)
+}
+
+/// loads the libraries in `Ferrex/UserLibs`, only the first call does anything
+pub fn load() {
+    LOADED.get_or_init(|| {
+        load_all().unwrap_or_else(|e| {
+            let _ = err!("Failed to load user libs: {}", e.to_string());
+            Vec::new()
+        })
+    });
+}
+
+fn elf_needed<Elf: FileHeader>(elf: &ElfFile<Elf>, data: &[u8]) -> Result<Vec<String>, DynErr> {
+    let endian = elf.endian();
+    let sections = elf.elf_section_table();
+
+    // the index is the one of the string table the entries point into
+    let Some((entries, strings)) = sections.dynamic(endian, data)? else {
+        return Ok(Vec::new());
+    };
+
+    let strings = sections.strings(endian, data, strings)?;
+
+    let mut needed = Vec::new();
+
+    for entry in entries {
+        if entry.tag32(endian) == Some(DT_NEEDED as _) {
+            needed.push(String::from_utf8_lossy(entry.string(endian, strings)?).to_string());
+        }
+    }
+
+    Ok(needed)
+}
+
+/// the libraries `path` links against
+fn needed_libraries(path: &Path) -> Result<Vec<String>, DynErr> {
+    let data = fs::read(path)?;
+
+    let needed = match object::File::parse(&*data)? {
+        object::File::Elf32(elf) => elf_needed(&elf, &data)?,
+        object::File::Elf64(elf) => elf_needed(&elf, &data)?,
+        file => {
+            let mut needed: Vec<String> = file
+                .imports()?
+                .iter()
+                .map(|import| String::from_utf8_lossy(import.library()).to_string())
+                .collect();
+
+            needed.sort();
+            needed.dedup();
+            needed
+        }
+    };
+
+    Ok(needed)
+}
+
+/// logs which user libs the mod at `path` links against
+pub fn report_dependencies(path: &Path) {
+    let Some(loaded) = LOADED.get().filter(|loaded| !loaded.is_empty()) else {
+        return;
+    };
+
+    let needed = match needed_libraries(path) {
+        Ok(needed) => needed,
+        Err(e) => {
+            let _ = err!("Failed to read the imports of {}: {}", path.display(), e.to_string());
+            return;
+        }
+    };
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    for lib in needed {
+        // windows doesn't care about case
+        if loaded.iter().any(|l| l.eq_ignore_ascii_case(&lib)) {
+            let _ = log!("{} needs user lib {}", name, lib);
+        }
+    }
+}
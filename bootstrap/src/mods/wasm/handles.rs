@@ -0,0 +1,115 @@
+//! wasm mods never see host pointers, they get ids into this table instead
+//!
+//! class handles live as long as the process. objects are pinned with a gc handle while the
+//! mod holds them, since the garbage collector can't see the table, and released after every
+//! callback.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use lazy_static::lazy_static;
+use unity_rs::{
+    common::{class::UnityClass, object::UnityObject},
+    runtime::{FerrexRuntime, RuntimeError},
+};
+
+use crate::{core, err};
+
+#[derive(Debug, Clone, Copy)]
+enum Handle {
+    Class(UnityClass),
+    /// the object, and the gc handle pinning it
+    Object(UnityObject, u32),
+}
+
+#[derive(Debug, Default)]
+struct Table {
+    next: u64,
+    handles: HashMap<u64, Handle>,
+    /// class pointer to handle, so looking up a class twice doesn't grow the table
+    classes: HashMap<usize, u64>,
+}
+
+impl Table {
+    fn insert(&mut self, handle: Handle) -> u64 {
+        // 0 stands for null on the guest side
+        self.next += 1;
+        self.handles.insert(self.next, handle);
+        self.next
+    }
+}
+
+lazy_static! {
+    static ref TABLE: Mutex<Table> = Mutex::new(Table::default());
+}
+
+pub fn class(class: UnityClass) -> u64 {
+    let mut table = TABLE.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let Some(id) = table.classes.get(&(class.inner as usize)) {
+        return *id;
+    }
+
+    let id = table.insert(Handle::Class(class));
+    table.classes.insert(class.inner as usize, id);
+    id
+}
+
+/// pins `object` and hands out a handle for it, `None` and null objects become 0
+pub fn object(object: Option<UnityObject>, runtime: &FerrexRuntime) -> Result<u64, RuntimeError> {
+    let object = match object {
+        Some(object) if !object.inner.is_null() => object,
+        _ => return Ok(0),
+    };
+
+    let gc_handle = object.new_gc_handle(true, runtime)?;
+
+    Ok(TABLE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(Handle::Object(object, gc_handle)))
+}
+
+pub fn get_class(id: u64) -> Option<UnityClass> {
+    match TABLE.lock().ok()?.handles.get(&id) {
+        Some(Handle::Class(class)) => Some(*class),
+        _ => None,
+    }
+}
+
+pub fn get_object(id: u64) -> Option<UnityObject> {
+    match TABLE.lock().ok()?.handles.get(&id) {
+        Some(Handle::Object(object, _)) => Some(*object),
+        _ => None,
+    }
+}
+
+/// drops every object handle and unpins the objects, called once a callback into a mod returns
+pub fn release_objects() {
+    let mut gc_handles = Vec::new();
+
+    if let Ok(mut table) = TABLE.lock() {
+        table.handles.retain(|_, handle| match handle {
+            Handle::Class(_) => true,
+            Handle::Object(_, gc_handle) => {
+                gc_handles.push(*gc_handle);
+                false
+            }
+        });
+    }
+
+    if gc_handles.is_empty() {
+        return;
+    }
+
+    let freed = core::get_runtime().and_then(|runtime| {
+        for gc_handle in gc_handles {
+            runtime.free_gc_handle(gc_handle)?;
+        }
+
+        Ok(())
+    });
+
+    if let Err(e) = freed {
+        let _ = err!("Failed to release object handles: {}", e.to_string());
+    }
+}
@@ -0,0 +1,493 @@
+//! the functions wasm mods can import
+//!
+//! they never panic or hand out pointers, failures are logged and turn into 0 or an empty value.
+//! everything but logging needs a capability, see [`super::permissions`].
+
+// scotch passes arguments as references to owned values
+#![allow(clippy::ptr_arg)]
+
+use std::{
+    ffi::c_void,
+    fs,
+    path::{Component, Path, PathBuf},
+    ptr,
+};
+
+use scotch_host::host_function;
+use toml::Value;
+use unity_rs::{
+    common::{class::UnityClass, method::UnityMethod, object::UnityObject, string::UnityString},
+    runtime::FerrexRuntime,
+};
+
+use crate::{
+    core, err,
+    errors::{preferr::PreferenceError, DynErr},
+    log,
+    preferences::{schema::Schema, store},
+    warn,
+};
+
+use super::{
+    current, current_owner, handles,
+    permissions::{self, Capability},
+};
+
+/// namespaces whose classes reach outside of the game, they can't be looked up at all
+const BLOCKED_NAMESPACES: &[&str] = &[
+    "System.IO",
+    "System.Diagnostics",
+    "System.Reflection",
+    "System.Runtime.InteropServices",
+    "System.Net",
+];
+
+/// single classes that lead to the blocked namespaces, through reflection or the process
+const BLOCKED_CLASSES: &[(&str, &str)] = &[
+    ("System", "Activator"),
+    ("System", "AppDomain"),
+    ("System", "Environment"),
+    ("System", "Type"),
+];
+
+fn is_blocked(namespace: &str, name: &str) -> bool {
+    let blocked_namespace = BLOCKED_NAMESPACES.iter().any(|blocked| {
+        namespace
+            .strip_prefix(blocked)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    });
+
+    blocked_namespace || BLOCKED_CLASSES.contains(&(namespace, name))
+}
+
+/// whether the calling mod was granted `capability`, a refusal is logged once per mod
+fn permitted(function: &str, capability: Capability) -> bool {
+    let owner = current_owner();
+
+    if permissions::allows(owner, capability) {
+        return true;
+    }
+
+    if permissions::deny(owner, capability) {
+        let _ = err!(
+            "[{}] {} denied, the mod doesn't declare the {} capability",
+            current(),
+            function,
+            capability
+        );
+    }
+
+    false
+}
+
+/// runs `f` if the calling mod may call into the game, logging any error under its name
+fn report<T: Default>(function: &str, f: impl FnOnce(&FerrexRuntime) -> Result<T, DynErr>) -> T {
+    if !permitted(function, Capability::InvokeManaged) {
+        return T::default();
+    }
+
+    let result = core::get_runtime().and_then(f);
+
+    result.unwrap_or_else(|e| {
+        let _ = err!("[{}] {} failed: {}", current(), function, e.to_string());
+        T::default()
+    })
+}
+
+fn class_handle(handle: u64) -> Result<UnityClass, DynErr> {
+    Ok(handles::get_class(handle).ok_or("Invalid class handle")?)
+}
+
+fn object_handle(handle: u64) -> Result<UnityObject, DynErr> {
+    Ok(handles::get_object(handle).ok_or("Invalid object handle")?)
+}
+
+fn corlib_class(name: &str, runtime: &FerrexRuntime) -> Result<UnityClass, DynErr> {
+    Ok(UnityClass::find(Some("mscorlib"), "System", name, runtime)?)
+}
+
+fn class_name(class: &UnityClass, runtime: &FerrexRuntime) -> Result<String, DynErr> {
+    let namespace = class.get_namespace(runtime)?;
+    let name = class.get_name(runtime)?;
+
+    match namespace.is_empty() {
+        true => Ok(name),
+        false => Ok(format!("{}.{}", namespace, name)),
+    }
+}
+
+/// fails unless `object` can be passed where `expected` is, the runtime doesn't check
+fn check_type(expected: &UnityClass, object: &UnityObject, what: &str, runtime: &FerrexRuntime) -> Result<(), DynErr> {
+    let class = object.get_class(runtime)?;
+
+    if !expected.is_assignable_from(&class, runtime)? {
+        return Err(format!(
+            "{} is a {}, but has to be a {}",
+            what,
+            class_name(&class, runtime)?,
+            class_name(expected, runtime)?
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// the object `method` is called on, value types are passed unboxed
+fn instance(
+    method: &UnityMethod,
+    object: u64,
+    runtime: &FerrexRuntime,
+) -> Result<Option<UnityObject>, DynErr> {
+    if object == 0 {
+        return Ok(None);
+    }
+
+    let object = object_handle(object)?;
+    let class = method.get_class(runtime)?;
+
+    check_type(&class, &object, "The instance", runtime)?;
+
+    match class.is_valuetype(runtime)? {
+        true => Ok(Some(runtime.unbox_object(&object)?)),
+        false => Ok(Some(object)),
+    }
+}
+
+/// turns argument handles into the pointers runtime_invoke expects
+fn arguments(
+    method: &UnityMethod,
+    args: &[u64],
+    runtime: &FerrexRuntime,
+) -> Result<Vec<*mut c_void>, DynErr> {
+    let types = method.get_param_types(runtime)?;
+    let mut params = Vec::with_capacity(args.len());
+
+    for (index, (ty, handle)) in types.iter().zip(args).enumerate() {
+        if ty.is_byref(runtime)? {
+            return Err("ref and out parameters are not supported".into());
+        }
+
+        let class = ty.get_class(runtime)?;
+        let is_valuetype = class.is_valuetype(runtime)?;
+
+        if *handle == 0 {
+            if is_valuetype {
+                return Err("Value type arguments can't be null".into());
+            }
+
+            params.push(ptr::null_mut());
+            continue;
+        }
+
+        let object = object_handle(*handle)?;
+
+        // unboxing a different value type would read past the end of the box
+        check_type(&class, &object, &format!("Argument {}", index), runtime)?;
+
+        params.push(match is_valuetype {
+            true => runtime.unbox_object(&object)?.inner,
+            false => object.inner,
+        });
+    }
+
+    Ok(params)
+}
+
+fn invoke(
+    method: &UnityMethod,
+    object: Option<UnityObject>,
+    args: &[u64],
+    runtime: &FerrexRuntime,
+) -> Result<u64, DynErr> {
+    let mut params = arguments(method, args, runtime)?;
+    let params = match params.is_empty() {
+        true => None,
+        false => Some(&mut params),
+    };
+
+    let result = runtime.invoke_method(method, object.as_ref(), params)?;
+
+    Ok(handles::object(result, runtime)?)
+}
+
+#[host_function]
+pub fn log_info(message: &String) {
+    let _ = log!("[{}] {}", current(), message);
+}
+
+#[host_function]
+pub fn log_warning(message: &String) {
+    let _ = warn!("[{}] {}", current(), message);
+}
+
+#[host_function]
+pub fn log_error(message: &String) {
+    let _ = err!("[{}] {}", current(), message);
+}
+
+/// returns a class handle, or 0 if the class doesn't exist
+#[host_function]
+pub fn find_class(assembly: &String, namespace: &String, name: &String) -> u64 {
+    report("find_class", |runtime| {
+        if is_blocked(namespace, name) {
+            return Err(format!("{}.{} is outside of what wasm mods may use", namespace, name).into());
+        }
+
+        let class = UnityClass::find(Some(assembly), namespace, name, runtime)?;
+
+        Ok(handles::class(class))
+    })
+}
+
+/// invokes `method` on `object`, or statically if `object` is 0, and returns the result handle
+#[host_function]
+pub fn invoke_method(class: u64, object: u64, method: &String, args: &Vec<u64>) -> u64 {
+    report("invoke_method", |runtime| {
+        let class = class_handle(class)?;
+        let method = class.get_method(method, args.len() as i32, runtime)?;
+        let object = instance(&method, object, runtime)?;
+
+        invoke(&method, object, args, runtime)
+    })
+}
+
+#[host_function]
+pub fn get_property(class: u64, object: u64, name: &String) -> u64 {
+    report("get_property", |runtime| {
+        let class = class_handle(class)?;
+        let property = class.get_property(name, runtime)?;
+        let getter = runtime.get_property_get_method(&property)?;
+        let object = instance(&getter, object, runtime)?;
+
+        invoke(&getter, object, &[], runtime)
+    })
+}
+
+#[host_function]
+pub fn set_property(class: u64, object: u64, name: &String, value: u64) {
+    report("set_property", |runtime| {
+        let class = class_handle(class)?;
+        let property = class.get_property(name, runtime)?;
+        let setter = runtime.get_property_set_method(&property)?;
+        let object = instance(&setter, object, runtime)?;
+
+        invoke(&setter, object, &[value], runtime).map(|_| ())
+    })
+}
+
+#[host_function]
+pub fn new_string(value: &String) -> u64 {
+    report("new_string", |runtime| {
+        let string = runtime.new_string(value)?;
+
+        let string = UnityObject {
+            inner: string.inner,
+        };
+
+        Ok(handles::object(Some(string), runtime)?)
+    })
+}
+
+#[host_function]
+pub fn read_string(handle: u64) -> String {
+    report("read_string", |runtime| {
+        let object = object_handle(handle)?;
+        let class = object.get_class(runtime)?;
+
+        if class.get_name(runtime)? != "String" || class.get_namespace(runtime)? != "System" {
+            return Err("Handle is not a System.String".into());
+        }
+
+        Ok(UnityString {
+            inner: object.inner,
+        }
+        .to_string(runtime)?)
+    })
+}
+
+macro_rules! primitives {
+    ($($ty:ty => $name:literal, $box_fn:ident, $unbox_fn:ident;)*) => {
+        $(
+            #[host_function]
+            pub fn $box_fn(value: $ty) -> u64 {
+                report(stringify!($box_fn), |runtime| {
+                    let mut value = value;
+                    let class = corlib_class($name, runtime)?;
+                    let object = class.box_value(ptr::addr_of_mut!(value).cast(), runtime)?;
+
+                    Ok(handles::object(Some(object), runtime)?)
+                })
+            }
+
+            #[host_function]
+            pub fn $unbox_fn(handle: u64) -> $ty {
+                report(stringify!($unbox_fn), |runtime| {
+                    let object = object_handle(handle)?;
+                    let class = object.get_class(runtime)?;
+
+                    // reading a different type would read past the end of the box
+                    if class.get_name(runtime)? != $name || class.get_namespace(runtime)? != "System" {
+                        return Err(format!("Handle is not a System.{}", $name).into());
+                    }
+
+                    let value = runtime.unbox_object(&object)?;
+                    Ok(unsafe { *value.inner.cast::<$ty>() })
+                })
+            }
+        )*
+    };
+}
+
+primitives! {
+    bool => "Boolean", box_bool, unbox_bool;
+    i32 => "Int32", box_i32, unbox_i32;
+    i64 => "Int64", box_i64, unbox_i64;
+    f32 => "Single", box_f32, unbox_f32;
+    f64 => "Double", box_f64, unbox_f64;
+}
+
+/// like [`report`], for functions that only touch the calling mod's preferences
+fn report_preference<T: Default>(function: &str, f: impl FnOnce(u64) -> Result<T, PreferenceError>) -> T {
+    if !permitted(function, Capability::Config) {
+        return T::default();
+    }
+
+    f(current_owner()).unwrap_or_else(|e| {
+        let _ = err!("[{}] {} failed: {}", current(), function, e.to_string());
+        T::default()
+    })
+}
+
+/// declares preferences from a toml schema, see [`crate::preferences::schema`]
+#[host_function]
+pub fn declare_preferences(schema: &String) -> bool {
+    report_preference("declare_preferences", |owner| {
+        store::declare(owner, Schema::parse(schema)?).map(|_| true)
+    })
+}
+
+/// returns an entry as a toml value, or an empty string if it doesn't exist
+#[host_function]
+pub fn get_preference(category: &String, key: &String) -> String {
+    report_preference("get_preference", |owner| {
+        Ok(store::get(owner, category, key)?.to_string())
+    })
+}
+
+/// sets an entry from a toml value
+#[host_function]
+pub fn set_preference(category: &String, key: &String, value: &String) -> bool {
+    report_preference("set_preference", |owner| {
+        store::set(owner, category, key, store::parse_literal(value)?).map(|_| true)
+    })
+}
+
+#[host_function]
+pub fn save_preferences() -> bool {
+    report_preference("save_preferences", |owner| store::save(owner).map(|_| true))
+}
+
+macro_rules! typed_preferences {
+    ($($ty:ty => $variant:ident, $get:ident, $set:ident;)*) => {
+        $(
+            #[host_function]
+            pub fn $get(category: &String, key: &String) -> $ty {
+                report_preference(stringify!($get), |owner| {
+                    match store::get(owner, category, key)? {
+                        Value::$variant(value) => Ok(value),
+                        value => Err(PreferenceError::InvalidValue(
+                            category.clone(),
+                            key.clone(),
+                            format!("stored as {}", value.type_str()),
+                        )),
+                    }
+                })
+            }
+
+            #[host_function]
+            pub fn $set(category: &String, key: &String, value: $ty) -> bool {
+                report_preference(stringify!($set), |owner| {
+                    store::set(owner, category, key, Value::$variant(value.clone())).map(|_| true)
+                })
+            }
+        )*
+    };
+}
+
+typed_preferences! {
+    bool => Boolean, get_bool_preference, set_bool_preference;
+    i64 => Integer, get_int_preference, set_int_preference;
+    f64 => Float, get_float_preference, set_float_preference;
+    String => String, get_string_preference, set_string_preference;
+}
+
+/// like [`report`], for functions that only touch the calling mod's data folder
+fn report_file<T: Default>(function: &str, path: &str, f: impl FnOnce(PathBuf) -> Result<T, DynErr>) -> T {
+    if !permitted(function, Capability::Filesystem) {
+        return T::default();
+    }
+
+    data_path(path).and_then(f).unwrap_or_else(|e| {
+        let _ = err!("[{}] {} {} failed: {}", current(), function, path, e.to_string());
+        T::default()
+    })
+}
+
+/// resolves a relative path inside the calling mod's data folder, nothing may lead out of it
+fn data_path(path: &str) -> Result<PathBuf, DynErr> {
+    let data_dir = permissions::data_dir(current_owner()).ok_or("The mod has no data folder")?;
+    let path = Path::new(path);
+
+    if !path.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+        return Err("Paths have to be relative, and stay inside the data folder".into());
+    }
+
+    Ok(data_dir.join(path))
+}
+
+/// returns the content of a file, or nothing if it can't be read
+#[host_function]
+pub fn read_file(path: &String) -> Vec<u8> {
+    report_file("read_file", path, |path| Ok(fs::read(path)?))
+}
+
+/// writes a file, creating the folders leading to it
+#[host_function]
+pub fn write_file(path: &String, content: &Vec<u8>) -> bool {
+    report_file("write_file", path, |path| {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, content)?;
+        Ok(true)
+    })
+}
+
+#[host_function]
+pub fn delete_file(path: &String) -> bool {
+    report_file("delete_file", path, |path| {
+        fs::remove_file(path)?;
+        Ok(true)
+    })
+}
+
+/// the names of the entries in a folder, an empty path lists the data folder itself
+#[host_function]
+pub fn list_files(path: &String) -> Vec<String> {
+    report_file("list_files", path, |path| {
+        if !path.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut names = Vec::new();
+
+        for entry in fs::read_dir(path)? {
+            names.push(entry?.file_name().to_string_lossy().to_string());
+        }
+
+        names.sort();
+        Ok(names)
+    })
+}
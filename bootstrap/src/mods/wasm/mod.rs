@@ -0,0 +1,287 @@
+//! sandboxed mods, compiled to WebAssembly and run through scotch
+
+pub mod handles;
+pub mod host;
+pub mod permissions;
+
+use std::{
+    cell::{Cell, RefCell},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use scotch_host::{guest_functions, make_imports, WasmPlugin};
+
+use crate::{
+    err,
+    errors::DynErr,
+    preferences::store::{self, Change},
+};
+
+use super::{
+    abi,
+    manifest::ModManifest,
+    scenes::{Scene, SceneEvent},
+    traits::{self, FerrexMod},
+};
+
+use host::*;
+
+#[guest_functions]
+extern "C" {
+    pub fn on_init();
+    pub fn on_scene_loaded(build_index: &i32, name: &String);
+    pub fn on_scene_event(kind: &i32, mode: &i32, build_index: &i32, name: &String, previous_build_index: &i32, previous_name: &String);
+    pub fn on_fixed_update();
+    pub fn on_update();
+    pub fn on_late_update();
+    pub fn on_quit();
+    pub fn ferrex_mod_manifest() -> String;
+    pub fn on_preference_changed(category: &String, key: &String, old: &String, new: &String);
+}
+
+thread_local! {
+    /// the mod whose callback is running, host functions log under its name
+    static CURRENT: RefCell<String> = const { RefCell::new(String::new()) };
+    /// the owner of that mod, host functions act on its behalf
+    static CURRENT_OWNER: Cell<u64> = const { Cell::new(0) };
+}
+
+pub(crate) fn current() -> String {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn current_owner() -> u64 {
+    CURRENT_OWNER.with(Cell::get)
+}
+
+pub struct WasmMod {
+    pub manifest: ModManifest,
+    pub path: PathBuf,
+    owner: u64,
+    plugin: WasmPlugin,
+    /// preference changes made during a callback, delivered once it returns
+    changes: Arc<Mutex<Vec<Change>>>,
+}
+
+impl std::fmt::Debug for WasmMod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmMod")
+            .field("id", &self.manifest.id)
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl WasmMod {
+    pub fn load(path: &Path) -> Result<Self, DynErr> {
+        let name = path
+            .file_stem()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let binary = fs::read(path)?;
+
+        let plugin = WasmPlugin::builder()
+            .with_state(())
+            .from_binary(&binary)?
+            .with_imports(make_imports!(
+                log_info,
+                log_warning,
+                log_error,
+                find_class,
+                invoke_method,
+                get_property,
+                set_property,
+                new_string,
+                read_string,
+                box_bool,
+                unbox_bool,
+                box_i32,
+                unbox_i32,
+                box_i64,
+                unbox_i64,
+                box_f32,
+                unbox_f32,
+                box_f64,
+                unbox_f64,
+                declare_preferences,
+                get_preference,
+                set_preference,
+                save_preferences,
+                get_bool_preference,
+                set_bool_preference,
+                get_int_preference,
+                set_int_preference,
+                get_float_preference,
+                set_float_preference,
+                get_string_preference,
+                set_string_preference,
+                read_file,
+                write_file,
+                delete_file,
+                list_files
+            ))
+            .finish()?;
+
+        let manifest = match ModManifest::sidecar(path)? {
+            Some(sidecar) => sidecar,
+            None => match plugin.function::<ferrex_mod_manifest>() {
+                Some(embedded) => ModManifest::parse(&embedded()?, &name)?,
+                None => ModManifest::new(&name, "0.0.0", ""),
+            },
+        };
+
+        let owner = traits::next_owner();
+        permissions::grant(owner, &manifest.id, manifest.display_name(), &manifest.capabilities)?;
+
+        Ok(WasmMod {
+            manifest,
+            path: path.to_path_buf(),
+            owner,
+            plugin,
+            changes: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+
+    /// marks this mod as the caller for host functions, and drops its object handles afterwards
+    fn enter<T>(&self, f: impl FnOnce() -> T) -> T {
+        CURRENT.with(|current| *current.borrow_mut() = self.name().to_string());
+        CURRENT_OWNER.with(|owner| owner.set(self.owner));
+
+        let result = f();
+
+        self.deliver_changes();
+
+        handles::release_objects();
+        CURRENT.with(|current| current.borrow_mut().clear());
+        CURRENT_OWNER.with(|owner| owner.set(0));
+
+        result
+    }
+
+    /// hands the preference changes made during a callback to the mod,
+    /// changes made while handling them are delivered after its next callback
+    fn deliver_changes(&self) {
+        let changes = std::mem::take(&mut *self.changes.lock().unwrap_or_else(|e| e.into_inner()));
+
+        let Some(callback) = self.plugin.function::<on_preference_changed>() else {
+            return;
+        };
+
+        for change in changes {
+            let result = callback(
+                &change.category,
+                &change.key,
+                &change.old.to_string(),
+                &change.new.to_string(),
+            );
+
+            if let Err(e) = result {
+                let _ = err!("[{}] on_preference_changed failed: {}", self.name(), e.to_string());
+            }
+        }
+    }
+}
+
+impl Drop for WasmMod {
+    fn drop(&mut self) {
+        permissions::remove_owner(self.owner);
+    }
+}
+
+impl FerrexMod for WasmMod {
+    fn owner(&self) -> u64 {
+        self.owner
+    }
+
+    fn manifest(&self) -> &ModManifest {
+        &self.manifest
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn init(&self) -> Result<(), DynErr> {
+        if self.plugin.function::<on_preference_changed>().is_some() {
+            let changes = self.changes.clone();
+
+            store::watch(
+                self.owner,
+                None,
+                None,
+                Arc::new(move |change| {
+                    changes
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .push(change.clone())
+                }),
+            )?;
+        }
+
+        match self.plugin.function::<on_init>() {
+            Some(callback) => self.enter(|| Ok(callback()?)),
+            None => Ok(()),
+        }
+    }
+
+    fn scene_event(&self, event: &SceneEvent) -> Result<(), DynErr> {
+        if let (Some(callback), SceneEvent::Loaded { scene, .. }) = (self.plugin.function::<on_scene_loaded>(), event) {
+            self.enter(|| callback(&scene.build_index, &scene.name))?;
+        }
+
+        let Some(callback) = self.plugin.function::<on_scene_event>() else {
+            return Ok(());
+        };
+
+        // the same values native mods get in `FerrexSceneEvent`
+        let none = Scene::none();
+
+        let (kind, mode, scene, previous) = match event {
+            SceneEvent::Loaded { scene, mode } => (abi::SCENE_LOADED, *mode as u32, scene, &none),
+            SceneEvent::Unloaded { scene } => (abi::SCENE_UNLOADED, 0, scene, &none),
+            SceneEvent::ActiveChanged { previous, current } => (abi::ACTIVE_SCENE_CHANGED, 0, current, previous),
+        };
+
+        self.enter(|| {
+            Ok(callback(
+                &(kind as i32),
+                &(mode as i32),
+                &scene.build_index,
+                &scene.name,
+                &previous.build_index,
+                &previous.name,
+            )?)
+        })
+    }
+
+    fn fixed_update(&self) -> Result<(), DynErr> {
+        match self.plugin.function::<on_fixed_update>() {
+            Some(callback) => self.enter(|| Ok(callback()?)),
+            None => Ok(()),
+        }
+    }
+
+    fn update(&self) -> Result<(), DynErr> {
+        match self.plugin.function::<on_update>() {
+            Some(callback) => self.enter(|| Ok(callback()?)),
+            None => Ok(()),
+        }
+    }
+
+    fn late_update(&self) -> Result<(), DynErr> {
+        match self.plugin.function::<on_late_update>() {
+            Some(callback) => self.enter(|| Ok(callback()?)),
+            None => Ok(()),
+        }
+    }
+
+    fn quit(&self) -> Result<(), DynErr> {
+        match self.plugin.function::<on_quit>() {
+            Some(callback) => self.enter(|| Ok(callback()?)),
+            None => Ok(()),
+        }
+    }
+}
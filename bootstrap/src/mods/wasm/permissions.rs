@@ -0,0 +1,154 @@
+//! what a wasm mod may do besides logging, declared in its manifest
+//!
+//! ```toml
+//! capabilities = ["invoke_managed", "filesystem"]
+//! ```
+//!
+//! host functions check the calling mod's capabilities before doing anything. a denied call
+//! is reported once per mod and capability, and returns 0 or an empty value like any failure.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::{errors::DynErr, log, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// find classes, call methods, read and write properties, create and read managed values.
+    /// the game's own code can do anything, so this amounts to full trust, even though
+    /// classes that reach outside of the game, like `System.IO`, can't be looked up
+    InvokeManaged,
+    /// hook game methods, not available to wasm mods yet, so it is always denied
+    InstallHooks,
+    /// read and write files in its own data folder, `Ferrex/UserData/<mod id>/`
+    Filesystem,
+    /// declare, read and change its own preferences
+    Config,
+}
+
+impl Capability {
+    fn describe(&self) -> &'static str {
+        match self {
+            Capability::InvokeManaged => "call into the game (full trust)",
+            Capability::InstallHooks => "hook game methods",
+            Capability::Filesystem => "use files in its data folder",
+            Capability::Config => "use its preferences",
+        }
+    }
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Capability::InvokeManaged => "invoke_managed",
+            Capability::InstallHooks => "install_hooks",
+            Capability::Filesystem => "filesystem",
+            Capability::Config => "config",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug)]
+struct Grant {
+    capabilities: BTreeSet<Capability>,
+    /// canonical, only set with the filesystem capability
+    data_dir: Option<PathBuf>,
+}
+
+lazy_static! {
+    static ref GRANTS: Mutex<HashMap<u64, Grant>> = Mutex::new(HashMap::new());
+    /// capabilities a mod was refused, so each is only reported once
+    static ref DENIED: Mutex<HashSet<(u64, Capability)>> = Mutex::new(HashSet::new());
+}
+
+/// creates `Ferrex/UserData/<id>/`, and makes sure it really is inside `Ferrex/UserData`
+fn create_data_dir(id: &str) -> Result<PathBuf, DynErr> {
+    let user_data = Path::new("Ferrex").join("UserData");
+    let data_dir = user_data.join(id);
+
+    fs::create_dir_all(&data_dir)?;
+
+    let (user_data, data_dir) = (user_data.canonicalize()?, data_dir.canonicalize()?);
+
+    match data_dir.parent() == Some(user_data.as_path()) {
+        true => Ok(data_dir),
+        false => Err(format!("{} is outside of {}", data_dir.display(), user_data.display()).into()),
+    }
+}
+
+/// grants `owner` its declared capabilities, and logs what they allow
+pub fn grant(owner: u64, id: &str, name: &str, capabilities: &[Capability]) -> Result<(), DynErr> {
+    let mut capabilities: BTreeSet<Capability> = capabilities.iter().copied().collect();
+
+    // declaring it is fine, so manifests don't have to change once hooks are supported
+    if capabilities.remove(&Capability::InstallHooks) {
+        warn!("[{}] Wasm mods can't hook methods yet, install_hooks is denied", name)?;
+    }
+
+    let data_dir = match capabilities.contains(&Capability::Filesystem) {
+        true => Some(create_data_dir(id)?),
+        false => None,
+    };
+
+    match capabilities.is_empty() {
+        true => log!("[{}] Permissions: none, it may only log", name)?,
+        false => {
+            let summary: Vec<&str> = capabilities.iter().map(Capability::describe).collect();
+            log!("[{}] Permissions: {}", name, summary.join(", "))?;
+        }
+    }
+
+    GRANTS.lock().unwrap_or_else(|e| e.into_inner()).insert(
+        owner,
+        Grant {
+            capabilities,
+            data_dir,
+        },
+    );
+
+    Ok(())
+}
+
+pub fn allows(owner: u64, capability: Capability) -> bool {
+    GRANTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&owner)
+        .is_some_and(|grant| grant.capabilities.contains(&capability))
+}
+
+/// records that `owner` was refused `capability`, returns whether that's the first time
+pub fn deny(owner: u64, capability: Capability) -> bool {
+    DENIED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert((owner, capability))
+}
+
+/// the folder a mod with the filesystem capability may use
+pub fn data_dir(owner: u64) -> Option<PathBuf> {
+    GRANTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&owner)
+        .and_then(|grant| grant.data_dir.clone())
+}
+
+/// forgets everything about an unloaded mod
+pub fn remove_owner(owner: u64) {
+    GRANTS.lock().unwrap_or_else(|e| e.into_inner()).remove(&owner);
+    DENIED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|(denied, _)| *denied != owner);
+}
@@ -0,0 +1,4 @@
+//! typed, per-mod preferences, similar to MelonPreferences
+
+pub mod schema;
+pub mod store;
@@ -0,0 +1,193 @@
+//! the categories and entries a mod declares
+//!
+//! mods describe their preferences as toml, for example
+//!
+//! ```toml
+//! version = 2
+//!
+//! [categories.general]
+//! description = "General settings"
+//!
+//! [categories.general.entries.speed]
+//! type = "float"
+//! default = 1.0
+//! min = 0.0
+//! max = 10.0
+//! description = "How fast the player moves"
+//! # the entry used to be called general.velocity
+//! previous = ["general.velocity"]
+//!
+//! [categories.general.entries.mode]
+//! type = "enum"
+//! default = "normal"
+//! options = ["easy", "normal", "hard"]
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// the type of the items in a list entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Bool,
+    Int,
+    Float,
+    String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum EntryKind {
+    Bool,
+    Int {
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    Float {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    String,
+    Enum {
+        options: Vec<String>,
+    },
+    List {
+        items: ItemKind,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Entry {
+    #[serde(flatten)]
+    pub kind: EntryKind,
+    pub default: Value,
+    #[serde(default)]
+    pub description: String,
+    /// keys this entry was stored under before, as `category.key`
+    #[serde(default)]
+    pub previous: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Category {
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub entries: BTreeMap<String, Entry>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Schema {
+    /// bumped by the mod whenever it renames, retypes or removes entries
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub categories: BTreeMap<String, Category>,
+}
+
+impl ItemKind {
+    fn validate(self, value: Value) -> Result<Value, String> {
+        match self {
+            ItemKind::Bool => EntryKind::Bool.validate(value),
+            ItemKind::Int => EntryKind::Int { min: None, max: None }.validate(value),
+            ItemKind::Float => EntryKind::Float { min: None, max: None }.validate(value),
+            ItemKind::String => EntryKind::String.validate(value),
+        }
+    }
+}
+
+impl EntryKind {
+    /// checks `value` against this kind, converting between ints and floats where nothing is lost
+    pub fn validate(&self, value: Value) -> Result<Value, String> {
+        match (self, value) {
+            (EntryKind::Bool, value @ Value::Boolean(_)) => Ok(value),
+
+            (EntryKind::Int { min, max }, value) => {
+                let value = match value {
+                    Value::Integer(value) => value,
+                    // `as` would saturate, 2^63 itself is already out of range
+                    Value::Float(value) if value.fract() == 0.0 && value >= i64::MIN as f64 && value < i64::MAX as f64 => {
+                        value as i64
+                    }
+                    Value::Float(value) if value.fract() == 0.0 => return Err(format!("{} is out of range", value)),
+                    value => return Err(format!("expected an int, got {}", value.type_str())),
+                };
+
+                if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+                    return Err(format!("{} is out of range", value));
+                }
+
+                Ok(Value::Integer(value))
+            }
+
+            (EntryKind::Float { min, max }, value) => {
+                let value = match value {
+                    Value::Float(value) => value,
+                    Value::Integer(value) => value as f64,
+                    value => return Err(format!("expected a float, got {}", value.type_str())),
+                };
+
+                if !value.is_finite() {
+                    return Err(format!("{} is not a finite number", value));
+                }
+
+                if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+                    return Err(format!("{} is out of range", value));
+                }
+
+                Ok(Value::Float(value))
+            }
+
+            (EntryKind::String, value @ Value::String(_)) => Ok(value),
+
+            (EntryKind::Enum { options }, Value::String(value)) => match options.contains(&value) {
+                true => Ok(Value::String(value)),
+                false => Err(format!("{} is not one of {}", value, options.join(", "))),
+            },
+
+            (EntryKind::List { items }, Value::Array(values)) => Ok(Value::Array(
+                values
+                    .into_iter()
+                    .map(|value| items.validate(value))
+                    .collect::<Result<_, _>>()?,
+            )),
+
+            (kind, value) => Err(format!("expected {}, got {}", kind.name(), value.type_str())),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            EntryKind::Bool => "a bool",
+            EntryKind::Int { .. } => "an int",
+            EntryKind::Float { .. } => "a float",
+            EntryKind::String => "a string",
+            EntryKind::Enum { .. } => "one of the enum options",
+            EntryKind::List { .. } => "a list",
+        }
+    }
+}
+
+impl Schema {
+    pub fn parse(schema: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(schema)
+    }
+
+    /// adds the categories of `other`, replacing entries declared twice
+    pub fn merge(&mut self, other: Schema) {
+        self.version = self.version.max(other.version);
+
+        for (name, category) in other.categories {
+            let existing = self.categories.entry(name).or_default();
+
+            if !category.description.is_empty() {
+                existing.description = category.description;
+            }
+
+            existing.entries.extend(category.entries);
+        }
+    }
+}
@@ -0,0 +1,444 @@
+//! the values of every mod's preferences, saved to `Ferrex/UserData/<mod id>.toml`
+
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use lazy_static::lazy_static;
+use toml::{Table, Value};
+
+use crate::{errors::preferr::PreferenceError, err, log, warn};
+
+use super::schema::Schema;
+
+lazy_static! {
+    /// keyed by the owner of the mod the preferences belong to
+    static ref STORES: Mutex<HashMap<u64, Store>> = Mutex::new(HashMap::new());
+}
+
+const VERSION_KEY: &str = "schema_version";
+
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub category: String,
+    pub key: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+pub type Callback = Arc<dyn Fn(&Change) + Send + Sync>;
+
+/// a change callback, for one entry, one category, or everything when both are none
+struct Watch {
+    category: Option<String>,
+    key: Option<String>,
+    callback: Callback,
+}
+
+impl Watch {
+    fn matches(&self, change: &Change) -> bool {
+        self.category.as_ref().is_none_or(|c| *c == change.category)
+            && self.key.as_ref().is_none_or(|k| *k == change.key)
+    }
+}
+
+struct Store {
+    id: String,
+    schema: Schema,
+    /// the schema version the file was written with
+    version: u32,
+    /// one table per category, including entries that aren't declared (yet), so they survive a save
+    values: Table,
+    dirty: bool,
+    watches: Vec<Watch>,
+}
+
+/// the file `id`'s preferences are saved to, which has to be directly inside `Ferrex/UserData`
+fn path(id: &str) -> Result<PathBuf, PreferenceError> {
+    let user_data = Path::new("Ferrex").join("UserData");
+    let file = user_data.join(format!("{}.toml", id));
+
+    fs::create_dir_all(&user_data)?;
+
+    let parent = file.parent().and_then(|parent| parent.canonicalize().ok());
+
+    match parent == Some(user_data.canonicalize()?) {
+        true => Ok(file),
+        false => Err(PreferenceError::InvalidId(id.to_string())),
+    }
+}
+
+/// parses a single toml value, like `true`, `1.5` or `["a", "b"]`
+pub fn parse_literal(literal: &str) -> Result<Value, PreferenceError> {
+    let mut table: Table = toml::from_str(&format!("value = {}", literal))?;
+
+    Ok(table.remove("value").unwrap_or(Value::Table(Table::new())))
+}
+
+/// quotes `key` if it isn't a bare toml key
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    match bare {
+        true => key.to_string(),
+        false => Value::String(key.to_string()).to_string(),
+    }
+}
+
+fn comment(out: &mut String, text: &str) {
+    for line in text.lines() {
+        let _ = writeln!(out, "# {}", line);
+    }
+}
+
+impl Store {
+    fn load(id: &str) -> Self {
+        let mut store = Store {
+            id: id.to_string(),
+            schema: Schema::default(),
+            version: 0,
+            values: Table::new(),
+            dirty: false,
+            watches: Vec::new(),
+        };
+
+        let file = match path(id) {
+            Ok(file) => file,
+            Err(e) => {
+                let _ = warn!("Preferences of {} can't be saved: {}", id, e.to_string());
+                return store;
+            }
+        };
+
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(_) => return store,
+        };
+
+        match toml::from_str::<Table>(&content) {
+            Ok(mut values) => {
+                store.version = match values.remove(VERSION_KEY) {
+                    Some(Value::Integer(version)) => version.try_into().unwrap_or(0),
+                    _ => 0,
+                };
+                store.values = values;
+            }
+            Err(e) => {
+                // keep the broken file around instead of overwriting it with defaults
+                let backup = file.with_extension("toml.bak");
+                let _ = fs::copy(&file, &backup);
+                let _ = warn!(
+                    "Failed to parse {}, using defaults, the old file was saved as {}: {}",
+                    file.display(),
+                    backup.display(),
+                    e.to_string()
+                );
+            }
+        }
+
+        store
+    }
+
+    /// removes a stored value, so whatever is left over afterwards isn't declared
+    fn take(&mut self, category: &str, key: &str) -> Option<Value> {
+        match self.values.get_mut(category) {
+            Some(Value::Table(table)) => table.remove(key),
+            _ => None,
+        }
+    }
+
+    /// removes the value stored under the first of `previous` that exists, entries are `category.key`
+    fn take_previous(&mut self, previous: &[String]) -> Option<(String, Value)> {
+        previous.iter().find_map(|path| {
+            let (category, key) = path.split_once('.')?;
+            self.take(category, key).map(|value| (path.clone(), value))
+        })
+    }
+
+    fn insert(&mut self, category: &str, key: &str, value: Value) {
+        let table = self
+            .values
+            .entry(category.to_string())
+            .or_insert_with(|| Value::Table(Table::new()));
+
+        // a category that was stored as a plain value isn't worth keeping
+        if !table.is_table() {
+            *table = Value::Table(Table::new());
+        }
+
+        if let Value::Table(table) = table {
+            table.insert(key.to_string(), value);
+        }
+    }
+
+    fn declare(&mut self, mut schema: Schema) -> Result<(), PreferenceError> {
+        for (category_name, category) in schema.categories.iter_mut() {
+            for (key, entry) in category.entries.iter_mut() {
+                entry.default = entry.kind.validate(entry.default.clone()).map_err(|reason| {
+                    PreferenceError::InvalidDefault(category_name.clone(), key.clone(), reason)
+                })?;
+            }
+        }
+
+        // mods may declare in several steps, only some of which carry the version
+        let version = self.schema.version.max(schema.version);
+
+        if self.version != version {
+            let _ = log!(
+                "Migrating preferences of {} from version {} to {}",
+                self.id,
+                self.version,
+                version
+            );
+
+            self.version = version;
+            self.dirty = true;
+        }
+
+        for (category_name, category) in schema.categories.iter() {
+            for (key, entry) in category.entries.iter() {
+                let stored = match self.take(category_name, key) {
+                    Some(value) => Some(value),
+                    None => self.take_previous(&entry.previous).map(|(previous, value)| {
+                        let _ = log!("Moving {} of {} to {}.{}", previous, self.id, category_name, key);
+                        self.dirty = true;
+                        value
+                    }),
+                };
+
+                let value = match stored {
+                    Some(stored) => match entry.kind.validate(stored.clone()) {
+                        Ok(value) => {
+                            self.dirty |= value != stored;
+                            value
+                        }
+                        Err(reason) => {
+                            let _ = warn!(
+                                "Resetting {}.{} of {} to its default: {}",
+                                category_name,
+                                key,
+                                self.id,
+                                reason
+                            );
+
+                            self.dirty = true;
+                            entry.default.clone()
+                        }
+                    },
+                    None => {
+                        self.dirty = true;
+                        entry.default.clone()
+                    }
+                };
+
+                self.insert(category_name, key, value);
+            }
+        }
+
+        self.schema.merge(schema);
+
+        Ok(())
+    }
+
+    fn unknown(&self, category: &str, key: &str) -> PreferenceError {
+        PreferenceError::UnknownEntry(self.id.clone(), category.to_string(), key.to_string())
+    }
+
+    fn get(&self, category: &str, key: &str) -> Result<Value, PreferenceError> {
+        self.values
+            .get(category)
+            .and_then(|table| table.get(key))
+            .filter(|_| self.schema.categories.get(category).is_some_and(|c| c.entries.contains_key(key)))
+            .cloned()
+            .ok_or_else(|| self.unknown(category, key))
+    }
+
+    /// stores `value` and returns the change, or none if the value didn't change
+    fn set(&mut self, category: &str, key: &str, value: Value) -> Result<Option<Change>, PreferenceError> {
+        let entry = self
+            .schema
+            .categories
+            .get(category)
+            .and_then(|c| c.entries.get(key))
+            .ok_or_else(|| self.unknown(category, key))?;
+
+        let value = entry.kind.validate(value).map_err(|reason| {
+            PreferenceError::InvalidValue(category.to_string(), key.to_string(), reason)
+        })?;
+
+        let old = self.get(category, key)?;
+
+        if old == value {
+            return Ok(None);
+        }
+
+        self.insert(category, key, value.clone());
+        self.dirty = true;
+
+        Ok(Some(Change {
+            category: category.to_string(),
+            key: key.to_string(),
+            old,
+            new: value,
+        }))
+    }
+
+    /// the file content, with descriptions as comments
+    fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{} = {}", VERSION_KEY, self.version);
+
+        for (name, table) in self.values.iter() {
+            let Value::Table(table) = table else {
+                continue;
+            };
+
+            let category = self.schema.categories.get(name);
+
+            out.push('\n');
+
+            if let Some(category) = category {
+                comment(&mut out, &category.description);
+            }
+
+            let _ = writeln!(out, "[{}]", toml_key(name));
+
+            for (key, value) in table.iter() {
+                if let Some(entry) = category.and_then(|c| c.entries.get(key)) {
+                    comment(&mut out, &entry.description);
+                }
+
+                let _ = writeln!(out, "{} = {}", toml_key(key), value);
+            }
+        }
+
+        out
+    }
+
+    fn save(&mut self) -> Result<(), PreferenceError> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        let file = path(&self.id)?;
+
+        fs::write(&file, self.render())?;
+        self.dirty = false;
+
+        Ok(())
+    }
+}
+
+fn with_store<T>(owner: u64, f: impl FnOnce(&mut Store) -> Result<T, PreferenceError>) -> Result<T, PreferenceError> {
+    let mut stores = STORES.lock().unwrap_or_else(|e| e.into_inner());
+    let store = stores.get_mut(&owner).ok_or(PreferenceError::UnknownOwner)?;
+
+    f(store)
+}
+
+/// gives the mod identified by `owner` its preferences, read from its file
+pub fn register(owner: u64, id: &str) {
+    let store = Store::load(id);
+    STORES.lock().unwrap_or_else(|e| e.into_inner()).insert(owner, store);
+}
+
+/// saves and forgets the preferences of an unloaded mod, dropping its callbacks
+pub fn unregister(owner: u64) {
+    let store = STORES.lock().unwrap_or_else(|e| e.into_inner()).remove(&owner);
+
+    if let Some(mut store) = store {
+        if let Err(e) = store.save() {
+            let _ = err!("Failed to save preferences of {}: {}", store.id, e.to_string());
+        }
+    }
+}
+
+/// declares categories and entries, migrating stored values to them
+pub fn declare(owner: u64, schema: Schema) -> Result<(), PreferenceError> {
+    with_store(owner, |store| {
+        store.declare(schema)?;
+        store.save()
+    })
+}
+
+pub fn get(owner: u64, category: &str, key: &str) -> Result<Value, PreferenceError> {
+    with_store(owner, |store| store.get(category, key))
+}
+
+/// validates and stores `value`, calling the callbacks watching the entry if it changed
+pub fn set(owner: u64, category: &str, key: &str, value: Value) -> Result<(), PreferenceError> {
+    let (id, change, callbacks) = with_store(owner, |store| {
+        let change = store.set(category, key, value)?;
+
+        let callbacks: Vec<Callback> = match change.as_ref() {
+            Some(change) => store
+                .watches
+                .iter()
+                .filter(|w| w.matches(change))
+                .map(|w| w.callback.clone())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Ok((store.id.clone(), change, callbacks))
+    })?;
+
+    let Some(change) = change else {
+        return Ok(());
+    };
+
+    // called without the lock held, so callbacks can read and write preferences themselves
+    for callback in callbacks {
+        if panic::catch_unwind(AssertUnwindSafe(|| callback(&change))).is_err() {
+            let _ = err!(
+                "A change callback of {} panicked for {}.{}",
+                id,
+                change.category,
+                change.key
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// calls `callback` whenever a matching entry changes, none matches every category or key
+pub fn watch(
+    owner: u64,
+    category: Option<&str>,
+    key: Option<&str>,
+    callback: Callback,
+) -> Result<(), PreferenceError> {
+    with_store(owner, |store| {
+        store.watches.push(Watch {
+            category: category.map(str::to_string),
+            key: key.map(str::to_string),
+            callback,
+        });
+
+        Ok(())
+    })
+}
+
+pub fn save(owner: u64) -> Result<(), PreferenceError> {
+    with_store(owner, Store::save)
+}
+
+/// saves every mod's preferences, called when the game quits
+pub fn save_all() {
+    let mut stores = STORES.lock().unwrap_or_else(|e| e.into_inner());
+
+    for store in stores.values_mut() {
+        if let Err(e) = store.save() {
+            let _ = err!("Failed to save preferences of {}: {}", store.id, e.to_string());
+        }
+    }
+}
@@ -47,6 +47,11 @@ impl UnityAssembly {
         runtime.get_assembly_name(self)
     }
 
+    /// the version as `major.minor.build.revision`
+    pub fn get_version(&self, runtime: &FerrexRuntime) -> Result<String, RuntimeError> {
+        runtime.get_assembly_version(self)
+    }
+
     pub fn get_image(&self, runtime: &FerrexRuntime) -> Result<UnityImage, RuntimeError> {
         runtime.assembly_get_image(self)
     }
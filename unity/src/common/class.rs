@@ -4,7 +4,7 @@ use std::ffi::c_void;
 
 use crate::runtime::{RuntimeError, FerrexRuntime};
 
-use super::{property::UnityProperty, method::UnityMethod};
+use super::{property::UnityProperty, method::UnityMethod, field::UnityField, object::UnityObject};
 
 /// Represents a C# Class
 #[derive(Debug, Copy)]
@@ -24,10 +24,61 @@ impl Clone for UnityClass {
 }
 
 impl UnityClass {
+    /// looks a class up in the assembly named `assembly`, without `.dll`, or in every loaded
+    /// assembly if that's none
+    pub fn find(assembly: Option<&str>, namespace: &str, name: &str, runtime: &FerrexRuntime) -> Result<UnityClass, RuntimeError> {
+        let assemblies = runtime.get_assemblies()?;
+
+        let Some(assembly) = assembly else {
+            return assemblies
+                .iter()
+                .find_map(|assembly| assembly.get_class(namespace, name, runtime).ok())
+                .ok_or_else(|| match namespace.is_empty() {
+                    true => RuntimeError::ClassNotFound(name.to_string()),
+                    false => RuntimeError::ClassNotFound(format!("{}.{}", namespace, name)),
+                });
+        };
+
+        for candidate in assemblies {
+            if candidate.get_name(runtime)? == assembly {
+                return candidate.get_class(namespace, name, runtime);
+            }
+        }
+
+        Err(RuntimeError::AssemblyNotFound(assembly.to_string()))
+    }
+
     pub fn get_name(&self, runtime: &FerrexRuntime) -> Result<String, RuntimeError> {
         runtime.get_class_name(self)
     }
 
+    pub fn get_namespace(&self, runtime: &FerrexRuntime) -> Result<String, RuntimeError> {
+        runtime.get_class_namespace(self)
+    }
+
+    pub fn is_valuetype(&self, runtime: &FerrexRuntime) -> Result<bool, RuntimeError> {
+        runtime.is_class_valuetype(self)
+    }
+
+    pub fn is_enum(&self, runtime: &FerrexRuntime) -> Result<bool, RuntimeError> {
+        runtime.is_class_enum(self)
+    }
+
+    /// the size of an unboxed instance, only meaningful for value types
+    pub fn get_value_size(&self, runtime: &FerrexRuntime) -> Result<usize, RuntimeError> {
+        runtime.get_class_value_size(self)
+    }
+
+    /// whether an instance of `other` can be passed where this class is expected
+    pub fn is_assignable_from(&self, other: &UnityClass, runtime: &FerrexRuntime) -> Result<bool, RuntimeError> {
+        runtime.is_class_assignable_from(self, other)
+    }
+
+    /// boxes the value type instance at `value` into a managed object of this class
+    pub fn box_value(&self, value: *mut c_void, runtime: &FerrexRuntime) -> Result<UnityObject, RuntimeError> {
+        runtime.box_value(self, value)
+    }
+
     pub fn get_property(&self, name: &str, runtime: &FerrexRuntime) -> Result<UnityProperty, RuntimeError> {
         runtime.get_property(self, name)
     }
@@ -35,4 +86,46 @@ impl UnityClass {
     pub fn get_method(&self, name: &str, args: i32, runtime: &FerrexRuntime) -> Result<UnityMethod, RuntimeError> {
         runtime.get_method(name, args, self)
     }
+
+    pub fn get_field(&self, name: &str, runtime: &FerrexRuntime) -> Result<UnityField, RuntimeError> {
+        runtime.get_field(self, name)
+    }
+
+    /// returns the base class, or `None` for `System.Object` and interfaces
+    pub fn get_parent(&self, runtime: &FerrexRuntime) -> Result<Option<UnityClass>, RuntimeError> {
+        runtime.get_class_parent(self)
+    }
+
+    /// all methods declared on this class, inherited methods are not included
+    pub fn get_methods(&self, runtime: &FerrexRuntime) -> Result<Vec<UnityMethod>, RuntimeError> {
+        runtime.get_class_methods(self)
+    }
+
+    /// all fields declared on this class, inherited fields are not included
+    pub fn get_fields(&self, runtime: &FerrexRuntime) -> Result<Vec<UnityField>, RuntimeError> {
+        runtime.get_class_fields(self)
+    }
+
+    /// all properties declared on this class, inherited properties are not included
+    pub fn get_properties(&self, runtime: &FerrexRuntime) -> Result<Vec<UnityProperty>, RuntimeError> {
+        runtime.get_class_properties(self)
+    }
+
+    pub fn get_nested_types(&self, runtime: &FerrexRuntime) -> Result<Vec<UnityClass>, RuntimeError> {
+        runtime.get_class_nested_types(self)
+    }
+
+    pub fn get_interfaces(&self, runtime: &FerrexRuntime) -> Result<Vec<UnityClass>, RuntimeError> {
+        runtime.get_class_interfaces(self)
+    }
+
+    /// allocates an instance without running a constructor, invoke `.ctor` on it afterwards
+    pub fn new_object(&self, runtime: &FerrexRuntime) -> Result<UnityObject, RuntimeError> {
+        runtime.new_object(self)
+    }
+
+    /// the classes of the custom attributes on this class, not supported on il2cpp
+    pub fn get_attributes(&self, runtime: &FerrexRuntime) -> Result<Vec<UnityClass>, RuntimeError> {
+        runtime.get_class_attributes(self)
+    }
 }
@@ -0,0 +1,119 @@
+//! TODO
+
+use std::{
+    ffi::c_void,
+    mem::{self, MaybeUninit},
+};
+
+use crate::runtime::{FerrexRuntime, RuntimeError};
+
+use super::{class::UnityClass, object::UnityObject, ty::UnityType};
+
+/// the static bit of a field's attributes
+pub const FIELD_ATTRIBUTE_STATIC: i32 = 0x0010;
+
+/// Represents a C# Field
+#[derive(Debug, Copy)]
+#[repr(C)]
+pub struct UnityField {
+    /// The inner pointer to the Field
+    pub inner: *mut c_void,
+}
+
+unsafe impl Send for UnityField {}
+unsafe impl Sync for UnityField {}
+
+impl Clone for UnityField {
+    fn clone(&self) -> UnityField {
+        *self
+    }
+}
+
+impl UnityField {
+    pub fn new(pointer: *mut c_void) -> Result<Self, RuntimeError> {
+        if pointer.is_null() {
+            return Err(RuntimeError::NullPointer("pointer"));
+        }
+
+        Ok(UnityField { inner: pointer })
+    }
+
+    pub fn get_name(&self, runtime: &FerrexRuntime) -> Result<String, RuntimeError> {
+        runtime.get_field_name(self)
+    }
+
+    pub fn get_type(&self, runtime: &FerrexRuntime) -> Result<UnityType, RuntimeError> {
+        runtime.get_field_type(self)
+    }
+
+    /// the offset of the field inside of an instance, including the object header
+    pub fn get_offset(&self, runtime: &FerrexRuntime) -> Result<usize, RuntimeError> {
+        runtime.get_field_offset(self)
+    }
+
+    pub fn get_parent(&self, runtime: &FerrexRuntime) -> Result<UnityClass, RuntimeError> {
+        runtime.get_field_parent(self)
+    }
+
+    pub fn is_static(&self, runtime: &FerrexRuntime) -> Result<bool, RuntimeError> {
+        Ok(runtime.get_field_flags(self)? & FIELD_ATTRIBUTE_STATIC != 0)
+    }
+
+    /// how many bytes reading the field writes, a pointer for reference types
+    pub fn get_value_size(&self, runtime: &FerrexRuntime) -> Result<usize, RuntimeError> {
+        let class = self.get_type(runtime)?.get_class(runtime)?;
+
+        match class.is_valuetype(runtime)? {
+            true => class.get_value_size(runtime),
+            false => Ok(mem::size_of::<*mut c_void>()),
+        }
+    }
+
+    /// the runtime writes as many bytes as the field has, so `T` has to be exactly that big
+    fn check_size<T>(&self, runtime: &FerrexRuntime) -> Result<(), RuntimeError> {
+        let size = self.get_value_size(runtime)?;
+
+        match size == mem::size_of::<T>() {
+            true => Ok(()),
+            false => Err(RuntimeError::FieldSizeMismatch(self.get_name(runtime)?, size, mem::size_of::<T>())),
+        }
+    }
+
+    /// reads the value of the field on an instance
+    ///
+    /// reference type fields are read as a pointer, use `UnityObject` or `*mut c_void` for `T`.
+    /// value types are copied into `T`, which has to have the same size and layout, a field
+    /// of a different size is an error
+    pub fn get_value<T>(&self, object: &UnityObject, runtime: &FerrexRuntime) -> Result<T, RuntimeError> {
+        self.check_size::<T>(runtime)?;
+
+        let mut value = MaybeUninit::<T>::uninit();
+
+        runtime.get_field_value(self, object, value.as_mut_ptr().cast())?;
+
+        Ok(unsafe { value.assume_init() })
+    }
+
+    /// writes the value of the field on an instance
+    ///
+    /// value types take a pointer to the data, reference types take the object pointer itself
+    pub fn set_value(&self, object: &UnityObject, value: *mut c_void, runtime: &FerrexRuntime) -> Result<(), RuntimeError> {
+        runtime.set_field_value(self, object, value)
+    }
+
+    /// reads the value of a static field, see `get_value` for what `T` may be
+    pub fn get_static_value<T>(&self, runtime: &FerrexRuntime) -> Result<T, RuntimeError> {
+        self.check_size::<T>(runtime)?;
+
+        let mut value = MaybeUninit::<T>::uninit();
+
+        runtime.get_static_field_value(self, value.as_mut_ptr().cast())?;
+
+        Ok(unsafe { value.assume_init() })
+    }
+
+    /// writes the value of a static field, see `set_value` for how to pass `value`
+    pub fn set_static_value(&self, value: *mut c_void, runtime: &FerrexRuntime) -> Result<(), RuntimeError> {
+        runtime.set_static_field_value(self, value)
+    }
+}
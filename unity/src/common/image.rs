@@ -4,6 +4,8 @@ use std::ffi::{c_void, OsStr};
 
 use crate::runtime::{RuntimeError, FerrexRuntime};
 
+use super::{assembly::UnityAssembly, class::UnityClass};
+
 /// Represents a C# Image
 #[derive(Debug, Copy)]
 #[repr(C)]
@@ -26,4 +28,27 @@ impl UnityImage {
         let name = filename.as_ref().to_str().ok_or_else(|| RuntimeError::Passthrough("Failed to get string from path".to_string()))?;
         runtime.open_assembly(name)?.get_image(runtime)
     }
+
+    /// the name of the image, without the file extension
+    pub fn get_name(&self, runtime: &FerrexRuntime) -> Result<String, RuntimeError> {
+        runtime.get_image_name(self)
+    }
+
+    pub fn get_filename(&self, runtime: &FerrexRuntime) -> Result<String, RuntimeError> {
+        runtime.get_image_filename(self)
+    }
+
+    pub fn get_assembly(&self, runtime: &FerrexRuntime) -> Result<UnityAssembly, RuntimeError> {
+        runtime.get_image_assembly(self)
+    }
+
+    /// the module version id, regenerated by the compiler on every build
+    pub fn get_guid(&self, runtime: &FerrexRuntime) -> Result<String, RuntimeError> {
+        runtime.get_image_guid(self)
+    }
+
+    /// every type defined in the image, nested types included
+    pub fn get_classes(&self, runtime: &FerrexRuntime) -> Result<Vec<UnityClass>, RuntimeError> {
+        runtime.get_image_classes(self)
+    }
 }
@@ -4,10 +4,17 @@ use std::ffi::c_void;
 
 use crate::runtime::{RuntimeError, FerrexRuntime};
 
-use super::object::UnityObject;
+use super::{class::UnityClass, object::UnityObject, ty::UnityType};
 
 pub type MethodPointer = *mut c_void;
 
+/// the static bit of a method's attributes
+pub const METHOD_ATTRIBUTE_STATIC: i32 = 0x0010;
+/// the virtual bit of a method's attributes
+pub const METHOD_ATTRIBUTE_VIRTUAL: i32 = 0x0040;
+/// the abstract bit of a method's attributes
+pub const METHOD_ATTRIBUTE_ABSTRACT: i32 = 0x0400;
+
 /// Represents a C# Method
 #[derive(Debug, Copy)]
 #[repr(C)]
@@ -25,6 +32,26 @@ impl Clone for UnityMethod {
     }
 }
 
+/// A single parameter of a method
+#[derive(Debug, Clone)]
+pub struct MethodParameter {
+    pub name: String,
+    pub ty: UnityType,
+}
+
+/// The signature of a method, as returned by `UnityMethod::get_signature`
+#[derive(Debug, Clone)]
+pub struct MethodSignature {
+    pub name: String,
+    pub parameters: Vec<MethodParameter>,
+    pub return_type: UnityType,
+    pub is_static: bool,
+    pub is_virtual: bool,
+    pub is_abstract: bool,
+    /// the amount of generic parameters on the method itself, not its class
+    pub generic_arity: usize,
+}
+
 impl UnityMethod {
     pub fn new(pointer: MethodPointer) -> Result<Self, RuntimeError> {
         if pointer.is_null() {
@@ -40,4 +67,81 @@ impl UnityMethod {
     pub fn invoke(&self, object: Option<&UnityObject>, params: Option<&mut Vec<*mut c_void>>, runtime: &FerrexRuntime) -> Result<Option<UnityObject>, RuntimeError> {
         runtime.invoke_method(self, object, params)
     }
+
+    /// like [`invoke`](Self::invoke), but a managed exception is returned as an error instead of being left unhandled
+    pub fn try_invoke(&self, object: Option<&UnityObject>, params: Option<&mut Vec<*mut c_void>>, runtime: &FerrexRuntime) -> Result<Option<UnityObject>, RuntimeError> {
+        runtime.try_invoke_method(self, object, params)
+    }
+
+    pub fn get_class(&self, runtime: &FerrexRuntime) -> Result<UnityClass, RuntimeError> {
+        runtime.get_method_class(self)
+    }
+
+    /// the native code of the method, jit compiling it first on mono
+    pub fn compile(&self, runtime: &FerrexRuntime) -> Result<MethodPointer, RuntimeError> {
+        runtime.compile_method(self)
+    }
+
+    pub fn get_param_count(&self, runtime: &FerrexRuntime) -> Result<usize, RuntimeError> {
+        runtime.get_method_param_count(self)
+    }
+
+    pub fn get_param_types(&self, runtime: &FerrexRuntime) -> Result<Vec<UnityType>, RuntimeError> {
+        runtime.get_method_param_types(self)
+    }
+
+    pub fn get_param_names(&self, runtime: &FerrexRuntime) -> Result<Vec<String>, RuntimeError> {
+        runtime.get_method_param_names(self)
+    }
+
+    pub fn get_return_type(&self, runtime: &FerrexRuntime) -> Result<UnityType, RuntimeError> {
+        runtime.get_method_return_type(self)
+    }
+
+    pub fn get_flags(&self, runtime: &FerrexRuntime) -> Result<i32, RuntimeError> {
+        runtime.get_method_flags(self)
+    }
+
+    pub fn is_static(&self, runtime: &FerrexRuntime) -> Result<bool, RuntimeError> {
+        Ok(self.get_flags(runtime)? & METHOD_ATTRIBUTE_STATIC != 0)
+    }
+
+    pub fn is_virtual(&self, runtime: &FerrexRuntime) -> Result<bool, RuntimeError> {
+        Ok(self.get_flags(runtime)? & METHOD_ATTRIBUTE_VIRTUAL != 0)
+    }
+
+    /// the amount of generic parameters the method has of its own, read from the metadata on mono
+    pub fn get_generic_arity(&self, runtime: &FerrexRuntime) -> Result<usize, RuntimeError> {
+        runtime.get_method_generic_arity(self)
+    }
+
+    /// whether the method belongs to an instantiation like `Foo<int>`, which has code of its own
+    pub fn is_inflated(&self, runtime: &FerrexRuntime) -> Result<bool, RuntimeError> {
+        runtime.is_method_inflated(self)
+    }
+
+    pub fn get_signature(&self, runtime: &FerrexRuntime) -> Result<MethodSignature, RuntimeError> {
+        let types = self.get_param_types(runtime)?;
+        let names = self.get_param_names(runtime)?;
+        let flags = self.get_flags(runtime)?;
+
+        let parameters = types
+            .into_iter()
+            .enumerate()
+            .map(|(i, ty)| MethodParameter {
+                name: names.get(i).cloned().unwrap_or_else(|| format!("arg{}", i)),
+                ty,
+            })
+            .collect();
+
+        Ok(MethodSignature {
+            name: self.get_name(runtime)?,
+            parameters,
+            return_type: self.get_return_type(runtime)?,
+            is_static: flags & METHOD_ATTRIBUTE_STATIC != 0,
+            is_virtual: flags & METHOD_ATTRIBUTE_VIRTUAL != 0,
+            is_abstract: flags & METHOD_ATTRIBUTE_ABSTRACT != 0,
+            generic_arity: self.get_generic_arity(runtime)?,
+        })
+    }
 }
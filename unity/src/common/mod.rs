@@ -8,4 +8,6 @@ pub mod string;
 pub mod thread;
 pub mod class;
 pub mod image;
-pub mod property;
\ No newline at end of file
+pub mod property;
+pub mod field;
+pub mod ty;
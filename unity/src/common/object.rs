@@ -2,6 +2,10 @@
 
 use std::ffi::c_void;
 
+use crate::runtime::{FerrexRuntime, RuntimeError};
+
+use super::class::UnityClass;
+
 /// Represents a C# Object
 #[derive(Debug, Copy)]
 #[repr(C)]
@@ -17,4 +21,15 @@ impl Clone for UnityObject {
     fn clone(&self) -> UnityObject {
         UnityObject { ..*self }
     }
-}
\ No newline at end of file
+}
+
+impl UnityObject {
+    pub fn get_class(&self, runtime: &FerrexRuntime) -> Result<UnityClass, RuntimeError> {
+        runtime.get_object_class(self)
+    }
+
+    /// keeps the object alive until the handle is freed, a pinned object isn't moved either
+    pub fn new_gc_handle(&self, pinned: bool, runtime: &FerrexRuntime) -> Result<u32, RuntimeError> {
+        runtime.new_gc_handle(self, pinned)
+    }
+}
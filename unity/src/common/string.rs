@@ -32,4 +32,8 @@ impl UnityString {
     pub fn from_raw(from: *const i8, runtime: &FerrexRuntime) -> Result<UnityString, RuntimeError> {
         runtime.string_from_raw(from)
     }
+
+    pub fn to_string(&self, runtime: &FerrexRuntime) -> Result<String, RuntimeError> {
+        runtime.string_to_utf8(self)
+    }
 }
@@ -0,0 +1,41 @@
+//! TODO
+
+use std::ffi::c_void;
+
+use crate::runtime::{FerrexRuntime, RuntimeError};
+
+use super::class::UnityClass;
+
+/// Represents a C# Type
+#[derive(Debug, Copy)]
+#[repr(C)]
+pub struct UnityType {
+    /// The inner pointer to the Type
+    pub inner: *mut c_void,
+}
+
+unsafe impl Send for UnityType {}
+unsafe impl Sync for UnityType {}
+
+impl Clone for UnityType {
+    fn clone(&self) -> UnityType {
+        *self
+    }
+}
+
+impl UnityType {
+    /// the full name of the type, e.g. `System.Collections.Generic.List<System.Int32>`
+    pub fn get_name(&self, runtime: &FerrexRuntime) -> Result<String, RuntimeError> {
+        runtime.get_type_name(self)
+    }
+
+    /// the class backing this type, for arrays and generic instances this is the constructed class
+    pub fn get_class(&self, runtime: &FerrexRuntime) -> Result<UnityClass, RuntimeError> {
+        runtime.get_type_class(self)
+    }
+
+    /// whether the type is passed by reference, i.e. `ref` and `out` parameters
+    pub fn is_byref(&self, runtime: &FerrexRuntime) -> Result<bool, RuntimeError> {
+        runtime.is_type_byref(self)
+    }
+}
@@ -1,12 +1,15 @@
 //! TODO
 
-use std::ffi::c_char;
+use std::ffi::{c_char, c_int};
 
 use libc::c_void;
 
 use crate::{libs::{LibError, NativeLibrary, NativeMethod}, utils::libs::get_function_option};
 
-use super::types::{Il2CppDomain, Il2CppMethod, Il2CppObject, Il2CppString, Il2CppThread};
+use super::types::{
+    Il2CppAssembly, Il2CppClass, Il2CppDomain, Il2CppField, Il2CppImage, Il2CppMethod,
+    Il2CppObject, Il2CppProperty, Il2CppString, Il2CppThread, Il2CppType,
+};
 
 /// Various methods exported by il2cpp
 ///
@@ -32,20 +35,162 @@ pub struct Il2CppExports {
     pub il2cpp_domain_get: Option<NativeMethod<fn() -> *mut Il2CppDomain>>,
     pub il2cpp_add_internal_call: Option<NativeMethod<fn(*const c_char, *mut c_void)>>,
     pub il2cpp_string_new: Option<NativeMethod<fn(*const c_char) -> *mut Il2CppString>>,
+    /// returns all assemblies loaded into a domain, the count is written to the second argument
+    pub il2cpp_domain_get_assemblies:
+        Option<NativeMethod<fn(*mut Il2CppDomain, *mut usize) -> *mut *mut Il2CppAssembly>>,
+    pub il2cpp_domain_assembly_open:
+        Option<NativeMethod<fn(*mut Il2CppDomain, *const c_char) -> *mut Il2CppAssembly>>,
+    pub il2cpp_assembly_get_image:
+        Option<NativeMethod<fn(*mut Il2CppAssembly) -> *mut Il2CppImage>>,
+    pub il2cpp_image_get_name: Option<NativeMethod<fn(*mut Il2CppImage) -> *const c_char>>,
+    pub il2cpp_class_from_name: Option<
+        NativeMethod<fn(*mut Il2CppImage, *const c_char, *const c_char) -> *mut Il2CppClass>,
+    >,
+    pub il2cpp_class_get_name: Option<NativeMethod<fn(*mut Il2CppClass) -> *const c_char>>,
+    pub il2cpp_class_get_method_from_name:
+        Option<NativeMethod<fn(*mut Il2CppClass, *const c_char, c_int) -> *mut Il2CppMethod>>,
+    pub il2cpp_class_get_property_from_name:
+        Option<NativeMethod<fn(*mut Il2CppClass, *const c_char) -> *mut Il2CppProperty>>,
+    pub il2cpp_property_get_name: Option<NativeMethod<fn(*mut Il2CppProperty) -> *const c_char>>,
+    pub il2cpp_property_get_get_method:
+        Option<NativeMethod<fn(*mut Il2CppProperty) -> *mut Il2CppMethod>>,
+    pub il2cpp_property_get_set_method:
+        Option<NativeMethod<fn(*mut Il2CppProperty) -> *mut Il2CppMethod>>,
+    pub il2cpp_object_unbox: Option<NativeMethod<fn(*mut Il2CppObject) -> *mut c_void>>,
+    pub il2cpp_value_box: Option<NativeMethod<fn(*mut Il2CppClass, *mut c_void) -> *mut Il2CppObject>>,
+    pub il2cpp_class_get_field_from_name:
+        Option<NativeMethod<fn(*mut Il2CppClass, *const c_char) -> *mut Il2CppField>>,
+    pub il2cpp_field_get_name: Option<NativeMethod<fn(*mut Il2CppField) -> *const c_char>>,
+    pub il2cpp_field_get_type: Option<NativeMethod<fn(*mut Il2CppField) -> *mut Il2CppType>>,
+    pub il2cpp_field_get_offset: Option<NativeMethod<fn(*mut Il2CppField) -> usize>>,
+    pub il2cpp_field_get_parent: Option<NativeMethod<fn(*mut Il2CppField) -> *mut Il2CppClass>>,
+    pub il2cpp_field_get_flags: Option<NativeMethod<fn(*mut Il2CppField) -> c_int>>,
+    pub il2cpp_field_get_value:
+        Option<NativeMethod<fn(*mut Il2CppObject, *mut Il2CppField, *mut c_void)>>,
+    pub il2cpp_field_set_value:
+        Option<NativeMethod<fn(*mut Il2CppObject, *mut Il2CppField, *mut c_void)>>,
+    /// static fields don't need a vtable on il2cpp, the class is initialized on access
+    pub il2cpp_field_static_get_value: Option<NativeMethod<fn(*mut Il2CppField, *mut c_void)>>,
+    pub il2cpp_field_static_set_value: Option<NativeMethod<fn(*mut Il2CppField, *mut c_void)>>,
+    pub il2cpp_type_get_name: Option<NativeMethod<fn(*mut Il2CppType) -> *const c_char>>,
+    pub il2cpp_free: Option<NativeMethod<fn(*mut c_void)>>,
+    pub il2cpp_class_get_namespace: Option<NativeMethod<fn(*mut Il2CppClass) -> *const c_char>>,
+    pub il2cpp_class_get_parent: Option<NativeMethod<fn(*mut Il2CppClass) -> *mut Il2CppClass>>,
+    pub il2cpp_class_get_methods:
+        Option<NativeMethod<fn(*mut Il2CppClass, *mut *mut c_void) -> *mut Il2CppMethod>>,
+    pub il2cpp_class_get_fields:
+        Option<NativeMethod<fn(*mut Il2CppClass, *mut *mut c_void) -> *mut Il2CppField>>,
+    pub il2cpp_class_get_properties:
+        Option<NativeMethod<fn(*mut Il2CppClass, *mut *mut c_void) -> *mut Il2CppProperty>>,
+    pub il2cpp_class_get_nested_types:
+        Option<NativeMethod<fn(*mut Il2CppClass, *mut *mut c_void) -> *mut Il2CppClass>>,
+    pub il2cpp_class_get_interfaces:
+        Option<NativeMethod<fn(*mut Il2CppClass, *mut *mut c_void) -> *mut Il2CppClass>>,
+    pub il2cpp_method_get_class: Option<NativeMethod<fn(*mut Il2CppMethod) -> *mut Il2CppClass>>,
+    pub il2cpp_method_get_param_count: Option<NativeMethod<fn(*mut Il2CppMethod) -> u32>>,
+    pub il2cpp_method_get_param:
+        Option<NativeMethod<fn(*mut Il2CppMethod, u32) -> *mut Il2CppType>>,
+    pub il2cpp_method_get_param_name:
+        Option<NativeMethod<fn(*mut Il2CppMethod, u32) -> *const c_char>>,
+    pub il2cpp_method_get_return_type:
+        Option<NativeMethod<fn(*mut Il2CppMethod) -> *mut Il2CppType>>,
+    pub il2cpp_method_get_flags: Option<NativeMethod<fn(*mut Il2CppMethod, *mut u32) -> u32>>,
+    pub il2cpp_method_is_generic: Option<NativeMethod<fn(*mut Il2CppMethod) -> bool>>,
+    pub il2cpp_method_is_inflated: Option<NativeMethod<fn(*mut Il2CppMethod) -> bool>>,
+    pub il2cpp_method_get_object:
+        Option<NativeMethod<fn(*mut Il2CppMethod, *mut Il2CppClass) -> *mut Il2CppObject>>,
+    pub il2cpp_object_get_class: Option<NativeMethod<fn(*mut Il2CppObject) -> *mut Il2CppClass>>,
+    pub il2cpp_array_length: Option<NativeMethod<fn(*mut Il2CppObject) -> u32>>,
+    pub il2cpp_image_get_filename: Option<NativeMethod<fn(*mut Il2CppImage) -> *const c_char>>,
+    pub il2cpp_image_get_assembly: Option<NativeMethod<fn(*mut Il2CppImage) -> *mut Il2CppAssembly>>,
+    pub il2cpp_image_get_class_count: Option<NativeMethod<fn(*mut Il2CppImage) -> usize>>,
+    pub il2cpp_image_get_class: Option<NativeMethod<fn(*mut Il2CppImage, usize) -> *mut Il2CppClass>>,
+    pub il2cpp_string_chars: Option<NativeMethod<fn(*mut Il2CppString) -> *const u16>>,
+    pub il2cpp_string_length: Option<NativeMethod<fn(*mut Il2CppString) -> i32>>,
+    pub il2cpp_class_from_type: Option<NativeMethod<fn(*mut Il2CppType) -> *mut Il2CppClass>>,
+    pub il2cpp_type_is_byref: Option<NativeMethod<fn(*mut Il2CppType) -> bool>>,
+    pub il2cpp_class_is_valuetype: Option<NativeMethod<fn(*mut Il2CppClass) -> bool>>,
+    pub il2cpp_class_is_enum: Option<NativeMethod<fn(*mut Il2CppClass) -> bool>>,
+    pub il2cpp_class_is_assignable_from: Option<NativeMethod<fn(*mut Il2CppClass, *mut Il2CppClass) -> bool>>,
+    pub il2cpp_class_value_size: Option<NativeMethod<fn(*mut Il2CppClass, *mut u32) -> i32>>,
+    pub il2cpp_object_new: Option<NativeMethod<fn(*mut Il2CppClass) -> *mut Il2CppObject>>,
+    pub il2cpp_gchandle_new: Option<NativeMethod<fn(*mut Il2CppObject, bool) -> u32>>,
+    pub il2cpp_gchandle_free: Option<NativeMethod<fn(u32)>>,
+    /// writes the message and stack trace of an exception to the buffer, truncating it
+    pub il2cpp_format_exception: Option<NativeMethod<fn(*mut Il2CppObject, *mut c_char, c_int)>>,
 }
 
 impl Il2CppExports {
     /// looks up and returns all methods from il2cpp
     pub fn new(lib: &NativeLibrary) -> Result<Il2CppExports, LibError> {
         Ok(Il2CppExports {
-            il2cpp_init: get_function_option(&lib, "il2cpp_init")?,
-            il2cpp_thread_current: get_function_option(&lib, "il2cpp_thread_current")?,
-            il2cpp_runtime_invoke: get_function_option(&lib, "il2cpp_runtime_invoke")?,
-            il2cpp_method_get_name: get_function_option(&lib, "il2cpp_method_get_name")?,
-            il2cpp_thread_attach: get_function_option(&lib, "il2cpp_thread_attach")?,
-            il2cpp_domain_get: get_function_option(&lib, "il2cpp_domain_get")?,
-            il2cpp_add_internal_call: get_function_option(&lib, "il2cpp_add_internal_call")?,
-            il2cpp_string_new: get_function_option(&lib, "il2cpp_string_new")?,
+            il2cpp_init: get_function_option(lib, "il2cpp_init")?,
+            il2cpp_thread_current: get_function_option(lib, "il2cpp_thread_current")?,
+            il2cpp_runtime_invoke: get_function_option(lib, "il2cpp_runtime_invoke")?,
+            il2cpp_method_get_name: get_function_option(lib, "il2cpp_method_get_name")?,
+            il2cpp_thread_attach: get_function_option(lib, "il2cpp_thread_attach")?,
+            il2cpp_domain_get: get_function_option(lib, "il2cpp_domain_get")?,
+            il2cpp_add_internal_call: get_function_option(lib, "il2cpp_add_internal_call")?,
+            il2cpp_string_new: get_function_option(lib, "il2cpp_string_new")?,
+            il2cpp_domain_get_assemblies: get_function_option(lib, "il2cpp_domain_get_assemblies")?,
+            il2cpp_domain_assembly_open: get_function_option(lib, "il2cpp_domain_assembly_open")?,
+            il2cpp_assembly_get_image: get_function_option(lib, "il2cpp_assembly_get_image")?,
+            il2cpp_image_get_name: get_function_option(lib, "il2cpp_image_get_name")?,
+            il2cpp_class_from_name: get_function_option(lib, "il2cpp_class_from_name")?,
+            il2cpp_class_get_name: get_function_option(lib, "il2cpp_class_get_name")?,
+            il2cpp_class_get_method_from_name: get_function_option(lib, "il2cpp_class_get_method_from_name")?,
+            il2cpp_class_get_property_from_name: get_function_option(lib, "il2cpp_class_get_property_from_name")?,
+            il2cpp_property_get_name: get_function_option(lib, "il2cpp_property_get_name")?,
+            il2cpp_property_get_get_method: get_function_option(lib, "il2cpp_property_get_get_method")?,
+            il2cpp_property_get_set_method: get_function_option(lib, "il2cpp_property_get_set_method")?,
+            il2cpp_object_unbox: get_function_option(lib, "il2cpp_object_unbox")?,
+            il2cpp_value_box: get_function_option(lib, "il2cpp_value_box")?,
+            il2cpp_class_get_field_from_name: get_function_option(lib, "il2cpp_class_get_field_from_name")?,
+            il2cpp_field_get_name: get_function_option(lib, "il2cpp_field_get_name")?,
+            il2cpp_field_get_type: get_function_option(lib, "il2cpp_field_get_type")?,
+            il2cpp_field_get_offset: get_function_option(lib, "il2cpp_field_get_offset")?,
+            il2cpp_field_get_parent: get_function_option(lib, "il2cpp_field_get_parent")?,
+            il2cpp_field_get_flags: get_function_option(lib, "il2cpp_field_get_flags")?,
+            il2cpp_field_get_value: get_function_option(lib, "il2cpp_field_get_value")?,
+            il2cpp_field_set_value: get_function_option(lib, "il2cpp_field_set_value")?,
+            il2cpp_field_static_get_value: get_function_option(lib, "il2cpp_field_static_get_value")?,
+            il2cpp_field_static_set_value: get_function_option(lib, "il2cpp_field_static_set_value")?,
+            il2cpp_type_get_name: get_function_option(lib, "il2cpp_type_get_name")?,
+            il2cpp_free: get_function_option(lib, "il2cpp_free")?,
+            il2cpp_class_get_namespace: get_function_option(lib, "il2cpp_class_get_namespace")?,
+            il2cpp_class_get_parent: get_function_option(lib, "il2cpp_class_get_parent")?,
+            il2cpp_class_get_methods: get_function_option(lib, "il2cpp_class_get_methods")?,
+            il2cpp_class_get_fields: get_function_option(lib, "il2cpp_class_get_fields")?,
+            il2cpp_class_get_properties: get_function_option(lib, "il2cpp_class_get_properties")?,
+            il2cpp_class_get_nested_types: get_function_option(lib, "il2cpp_class_get_nested_types")?,
+            il2cpp_class_get_interfaces: get_function_option(lib, "il2cpp_class_get_interfaces")?,
+            il2cpp_method_get_class: get_function_option(lib, "il2cpp_method_get_class")?,
+            il2cpp_method_get_param_count: get_function_option(lib, "il2cpp_method_get_param_count")?,
+            il2cpp_method_get_param: get_function_option(lib, "il2cpp_method_get_param")?,
+            il2cpp_method_get_param_name: get_function_option(lib, "il2cpp_method_get_param_name")?,
+            il2cpp_method_get_return_type: get_function_option(lib, "il2cpp_method_get_return_type")?,
+            il2cpp_method_get_flags: get_function_option(lib, "il2cpp_method_get_flags")?,
+            il2cpp_method_is_generic: get_function_option(lib, "il2cpp_method_is_generic")?,
+            il2cpp_method_is_inflated: get_function_option(lib, "il2cpp_method_is_inflated")?,
+            il2cpp_method_get_object: get_function_option(lib, "il2cpp_method_get_object")?,
+            il2cpp_object_get_class: get_function_option(lib, "il2cpp_object_get_class")?,
+            il2cpp_array_length: get_function_option(lib, "il2cpp_array_length")?,
+            il2cpp_image_get_filename: get_function_option(lib, "il2cpp_image_get_filename")?,
+            il2cpp_image_get_assembly: get_function_option(lib, "il2cpp_image_get_assembly")?,
+            il2cpp_image_get_class_count: get_function_option(lib, "il2cpp_image_get_class_count")?,
+            il2cpp_image_get_class: get_function_option(lib, "il2cpp_image_get_class")?,
+            il2cpp_string_chars: get_function_option(lib, "il2cpp_string_chars")?,
+            il2cpp_string_length: get_function_option(lib, "il2cpp_string_length")?,
+            il2cpp_class_from_type: get_function_option(lib, "il2cpp_class_from_type")?,
+            il2cpp_type_is_byref: get_function_option(lib, "il2cpp_type_is_byref")?,
+            il2cpp_class_is_valuetype: get_function_option(lib, "il2cpp_class_is_valuetype")?,
+            il2cpp_class_is_enum: get_function_option(lib, "il2cpp_class_is_enum")?,
+            il2cpp_class_is_assignable_from: get_function_option(lib, "il2cpp_class_is_assignable_from")?,
+            il2cpp_class_value_size: get_function_option(lib, "il2cpp_class_value_size")?,
+            il2cpp_object_new: get_function_option(lib, "il2cpp_object_new")?,
+            il2cpp_gchandle_new: get_function_option(lib, "il2cpp_gchandle_new")?,
+            il2cpp_gchandle_free: get_function_option(lib, "il2cpp_gchandle_free")?,
+            il2cpp_format_exception: get_function_option(lib, "il2cpp_format_exception")?,
         })
     }
 }
@@ -1,9 +1,8 @@
 //! TODO
 
 use std::{
-    ffi::{CStr, CString},
+    ffi::{c_char, c_int, CStr, CString},
     path::PathBuf,
-    ptr::addr_of_mut,
 };
 
 use libc::c_void;
@@ -16,14 +15,19 @@ use crate::{
         object::UnityObject,
         string::UnityString,
         thread::UnityThread, image::UnityImage, class::UnityClass, property::UnityProperty,
+        field::UnityField, ty::UnityType,
     },
     join_dll_path,
     libs::{self, NativeLibrary, NativeMethod},
     mono::AssemblyHookType,
     runtime::{Runtime, RuntimeError, RuntimeType},
+    utils::iter::collect_iter,
 };
 
-use self::{exports::Il2CppExports, types::Il2CppObject};
+use self::{
+    exports::Il2CppExports,
+    types::{Il2CppMethod, Il2CppObject},
+};
 
 pub mod exports;
 pub mod types;
@@ -55,6 +59,37 @@ impl Il2Cpp {
         };
         Ok(il2cpp)
     }
+
+    /// the message and stack trace of a managed exception
+    fn format_exception(&self, exc: *mut Il2CppObject) -> String {
+        let Some(function) = self.exports.clone().il2cpp_format_exception else {
+            return "unknown exception".to_string();
+        };
+
+        let mut buffer = [0 as c_char; 4096];
+        function(exc, buffer.as_mut_ptr(), buffer.len() as c_int);
+
+        unsafe { CStr::from_ptr(buffer.as_ptr()) }
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// frees memory allocated by il2cpp, e.g. strings returned by `il2cpp_type_get_name`
+    pub fn free(&self, ptr: *mut c_void) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_free
+            .ok_or(RuntimeError::MissingFunction("il2cpp_free"))?;
+
+        if ptr.is_null() {
+            return Err(RuntimeError::NullPointer("ptr"));
+        }
+
+        function(ptr);
+
+        Ok(())
+    }
 }
 
 impl Runtime for Il2Cpp {
@@ -196,10 +231,7 @@ impl Runtime for Il2Cpp {
     }
 
     fn new_string(&self, name: &str) -> Result<UnityString, RuntimeError> {
-        if name.is_empty() {
-            return Err(RuntimeError::EmptyString);
-        }
-
+        // empty strings are fine, unlike the names the other lookups take
         let native_str = CString::new(name)?;
 
         self.string_from_raw(native_str.as_ptr())
@@ -248,8 +280,8 @@ impl Runtime for Il2Cpp {
         };
 
         let params = match params {
-            Some(params) => addr_of_mut!(params[0]),
-            None => std::ptr::null_mut(),
+            Some(params) if !params.is_empty() => params.as_mut_ptr(),
+            _ => std::ptr::null_mut(),
         };
 
         let result = function(
@@ -290,62 +322,1243 @@ impl Runtime for Il2Cpp {
     }
 
     fn get_assemblies(&self) -> Result<Vec<UnityAssembly>, RuntimeError> {
-        Ok(Vec::new())
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_domain_get_assemblies
+            .ok_or(RuntimeError::MissingFunction("il2cpp_domain_get_assemblies"))?;
+
+        let domain = self.get_domain()?;
+        let mut size: usize = 0;
+
+        let assemblies = function(domain.inner.cast(), &mut size);
+
+        if assemblies.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_domain_get_assemblies"));
+        }
+
+        let assemblies = unsafe { std::slice::from_raw_parts(assemblies, size) };
+
+        Ok(assemblies
+            .iter()
+            .filter(|a| !a.is_null())
+            .map(|a| UnityAssembly { inner: a.cast() })
+            .collect())
     }
 
-    fn get_assembly_name(&self, _assembly: &UnityAssembly) -> Result<String, RuntimeError> {
-        Ok("stub".to_string())
+    /// il2cpp has no exported assembly name getter, so the image name is used instead
+    fn get_assembly_name(&self, assembly: &UnityAssembly) -> Result<String, RuntimeError> {
+        let image = self.assembly_get_image(assembly)?;
+
+        self.get_image_name(&image)
     }
 
-    fn open_assembly(&self, _name: &str) -> Result<UnityAssembly, RuntimeError> {
-        Err(RuntimeError::NotImplemented("open_assembly"))
+    fn open_assembly(&self, name: &str) -> Result<UnityAssembly, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_domain_assembly_open
+            .ok_or(RuntimeError::MissingFunction("il2cpp_domain_assembly_open"))?;
+
+        if name.is_empty() {
+            return Err(RuntimeError::EmptyString);
+        }
+
+        let assembly = function(
+            self.get_domain()?.inner.cast(),
+            CString::new(name)?.as_ptr(),
+        );
+
+        if assembly.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_domain_assembly_open"));
+        }
+
+        Ok(UnityAssembly {
+            inner: assembly.cast(),
+        })
     }
 
-    fn assembly_get_image(&self, _assembly: &UnityAssembly) -> Result<UnityImage, RuntimeError> {
-        Err(RuntimeError::NotImplemented("assembly_get_image"))
+    fn assembly_get_image(&self, assembly: &UnityAssembly) -> Result<UnityImage, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_assembly_get_image
+            .ok_or(RuntimeError::MissingFunction("il2cpp_assembly_get_image"))?;
+
+        if assembly.inner.is_null() {
+            return Err(RuntimeError::NullPointer("assembly"));
+        }
+
+        let image = function(assembly.inner.cast());
+
+        if image.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_assembly_get_image"));
+        }
+
+        Ok(UnityImage {
+            inner: image.cast(),
+        })
     }
 
-    fn get_class(&self, _assembly: &UnityAssembly, _namespace: String, _name: String) -> Result<UnityClass, RuntimeError> {
-        Err(RuntimeError::NotImplemented("get_class"))
+    fn get_class(
+        &self,
+        assembly: &UnityAssembly,
+        namespace: String,
+        name: String,
+    ) -> Result<UnityClass, RuntimeError> {
+        let image = self.assembly_get_image(assembly)?;
+
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_from_name
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_from_name"))?;
+
+        let class = function(
+            image.inner.cast(),
+            CString::new(namespace)?.as_ptr(),
+            CString::new(name)?.as_ptr(),
+        );
+
+        if class.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_class_from_name"));
+        }
+
+        Ok(UnityClass {
+            inner: class.cast(),
+        })
     }
 
-    fn get_class_name(&self, _class: &UnityClass) -> Result<String, RuntimeError> {
-        Ok("TODO".to_string())
+    fn get_class_name(&self, class: &UnityClass) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_get_name
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_get_name"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        let name = function(class.inner.cast());
+
+        if name.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_class_get_name"));
+        }
+
+        let name = unsafe { CStr::from_ptr(name) }.to_str()?;
+
+        Ok(name.to_string())
     }
 
-    fn get_property(&self, _class: &UnityClass, _name: &str) -> Result<UnityProperty, RuntimeError> {
-        Err(RuntimeError::NotImplemented("get_property"))
+    fn get_property(&self, class: &UnityClass, name: &str) -> Result<UnityProperty, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_get_property_from_name
+            .ok_or(RuntimeError::MissingFunction(
+                "il2cpp_class_get_property_from_name",
+            ))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        let prop = function(class.inner.cast(), CString::new(name)?.as_ptr());
+
+        if prop.is_null() {
+            return Err(RuntimeError::ReturnedNull(
+                "il2cpp_class_get_property_from_name",
+            ));
+        }
+
+        Ok(UnityProperty { inner: prop.cast() })
     }
 
-    fn get_property_name(&self, _prop: &UnityProperty) -> Result<String, RuntimeError> {
-        Err(RuntimeError::NotImplemented("get_property_name"))
+    fn get_property_name(&self, prop: &UnityProperty) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_property_get_name
+            .ok_or(RuntimeError::MissingFunction("il2cpp_property_get_name"))?;
+
+        if prop.inner.is_null() {
+            return Err(RuntimeError::NullPointer("property"));
+        }
+
+        let name = function(prop.inner.cast());
+
+        if name.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_property_get_name"));
+        }
+
+        let name = unsafe { CStr::from_ptr(name) }.to_str()?;
+
+        Ok(name.to_string())
     }
 
-    fn get_property_get_method(&self, _prop: &UnityProperty) -> Result<UnityMethod, RuntimeError> {
-        Err(RuntimeError::NotImplemented("get_property_get_get_method"))
+    fn get_property_get_method(&self, prop: &UnityProperty) -> Result<UnityMethod, RuntimeError> {
+        let function = &self.exports.clone().il2cpp_property_get_get_method.ok_or(
+            RuntimeError::MissingFunction("il2cpp_property_get_get_method"),
+        )?;
+
+        if prop.inner.is_null() {
+            return Err(RuntimeError::NullPointer("property"));
+        }
+
+        let method = function(prop.inner.cast());
+
+        if method.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_property_get_get_method"));
+        }
+
+        Ok(UnityMethod {
+            inner: method.cast(),
+        })
     }
 
-    fn get_property_set_method(&self, _prop: &UnityProperty) -> Result<UnityMethod, RuntimeError> {
-        Err(RuntimeError::NotImplemented("get_property_get_set_method"))
+    fn get_property_set_method(&self, prop: &UnityProperty) -> Result<UnityMethod, RuntimeError> {
+        let function = &self.exports.clone().il2cpp_property_get_set_method.ok_or(
+            RuntimeError::MissingFunction("il2cpp_property_get_set_method"),
+        )?;
+
+        if prop.inner.is_null() {
+            return Err(RuntimeError::NullPointer("property"));
+        }
+
+        let method = function(prop.inner.cast());
+
+        if method.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_property_get_set_method"));
+        }
+
+        Ok(UnityMethod {
+            inner: method.cast(),
+        })
     }
 
+    /// il2cpp methods are already compiled, this reads the native pointer off the MethodInfo
     fn get_unmanaged_thunk(&self, method: &UnityMethod) -> Result<MethodPointer, RuntimeError> {
         if method.inner.is_null() {
             return Err(RuntimeError::NullPointer("method"));
         }
 
-        Ok(method.inner)
+        let pointer = unsafe { (*method.inner.cast::<Il2CppMethod>()).method_pointer };
+
+        if pointer.is_null() {
+            return Err(RuntimeError::ReturnedNull("get_unmanaged_thunk"));
+        }
+
+        Ok(pointer)
+    }
+
+    fn get_method(
+        &self,
+        name: &str,
+        args: i32,
+        class: &UnityClass,
+    ) -> Result<UnityMethod, RuntimeError> {
+        let function = &self.exports.clone().il2cpp_class_get_method_from_name.ok_or(
+            RuntimeError::MissingFunction("il2cpp_class_get_method_from_name"),
+        )?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        if name.is_empty() {
+            return Err(RuntimeError::EmptyString);
+        }
+
+        let method = function(class.inner.cast(), CString::new(name)?.as_ptr(), args);
+
+        if method.is_null() {
+            return Err(RuntimeError::ReturnedNull(
+                "il2cpp_class_get_method_from_name",
+            ));
+        }
+
+        Ok(UnityMethod {
+            inner: method.cast(),
+        })
+    }
+
+    fn get_assembly_object(&self, _assembly: &UnityAssembly) -> Result<UnityObject, RuntimeError> {
+        Err(RuntimeError::NotImplemented(
+            "get_assembly_object is a mono only function",
+        ))
+    }
+
+    fn unbox_object(&self, object: &UnityObject) -> Result<UnityObject, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_object_unbox
+            .ok_or(RuntimeError::MissingFunction("il2cpp_object_unbox"))?;
+
+        if object.inner.is_null() {
+            return Err(RuntimeError::NullPointer("object"));
+        }
+
+        let object = function(object.inner.cast());
+
+        if object.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_object_unbox"));
+        }
+
+        Ok(UnityObject {
+            inner: object.cast(),
+        })
+    }
+
+    fn box_value(&self, class: &UnityClass, value: *mut c_void) -> Result<UnityObject, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_value_box
+            .ok_or(RuntimeError::MissingFunction("il2cpp_value_box"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        if value.is_null() {
+            return Err(RuntimeError::NullPointer("value"));
+        }
+
+        let object = function(class.inner.cast(), value);
+
+        if object.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_value_box"));
+        }
+
+        Ok(UnityObject {
+            inner: object.cast(),
+        })
+    }
+
+    fn get_field(&self, class: &UnityClass, name: &str) -> Result<UnityField, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_get_field_from_name
+            .ok_or(RuntimeError::MissingFunction(
+                "il2cpp_class_get_field_from_name",
+            ))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        if name.is_empty() {
+            return Err(RuntimeError::EmptyString);
+        }
+
+        let field = function(class.inner.cast(), CString::new(name)?.as_ptr());
+
+        if field.is_null() {
+            return Err(RuntimeError::ReturnedNull(
+                "il2cpp_class_get_field_from_name",
+            ));
+        }
+
+        Ok(UnityField {
+            inner: field.cast(),
+        })
+    }
+
+    fn get_field_name(&self, field: &UnityField) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_field_get_name
+            .ok_or(RuntimeError::MissingFunction("il2cpp_field_get_name"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        let name = function(field.inner.cast());
+
+        if name.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_field_get_name"));
+        }
+
+        let name = unsafe { CStr::from_ptr(name) }.to_str()?;
+
+        Ok(name.to_string())
+    }
+
+    fn get_field_type(&self, field: &UnityField) -> Result<UnityType, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_field_get_type
+            .ok_or(RuntimeError::MissingFunction("il2cpp_field_get_type"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        let ty = function(field.inner.cast());
+
+        if ty.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_field_get_type"));
+        }
+
+        Ok(UnityType { inner: ty.cast() })
+    }
+
+    fn get_field_offset(&self, field: &UnityField) -> Result<usize, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_field_get_offset
+            .ok_or(RuntimeError::MissingFunction("il2cpp_field_get_offset"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        let offset = function(field.inner.cast());
+
+        Ok(offset)
+    }
+
+    fn get_field_parent(&self, field: &UnityField) -> Result<UnityClass, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_field_get_parent
+            .ok_or(RuntimeError::MissingFunction("il2cpp_field_get_parent"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        let class = function(field.inner.cast());
+
+        if class.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_field_get_parent"));
+        }
+
+        Ok(UnityClass {
+            inner: class.cast(),
+        })
+    }
+
+    fn get_field_flags(&self, field: &UnityField) -> Result<i32, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_field_get_flags
+            .ok_or(RuntimeError::MissingFunction("il2cpp_field_get_flags"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        let flags = function(field.inner.cast());
+
+        Ok(flags)
     }
 
-    fn get_method(&self, _name: &str, _args: i32, _class: &UnityClass) -> Result<UnityMethod, RuntimeError> {
-        Err(RuntimeError::NotImplemented("get_method"))
+    fn get_field_value(
+        &self,
+        field: &UnityField,
+        object: &UnityObject,
+        value: *mut c_void,
+    ) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_field_get_value
+            .ok_or(RuntimeError::MissingFunction("il2cpp_field_get_value"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        if object.inner.is_null() {
+            return Err(RuntimeError::NullPointer("object"));
+        }
+
+        if value.is_null() {
+            return Err(RuntimeError::NullPointer("value"));
+        }
+
+        function(object.inner.cast(), field.inner.cast(), value);
+
+        Ok(())
     }
 
-    fn get_assembly_object(&self, _assembly: &UnityAssembly) -> Result<UnityObject, RuntimeError>  {
-        Err(RuntimeError::NotImplemented("get_assembly_object"))
+    fn set_field_value(
+        &self,
+        field: &UnityField,
+        object: &UnityObject,
+        value: *mut c_void,
+    ) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_field_set_value
+            .ok_or(RuntimeError::MissingFunction("il2cpp_field_set_value"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        if object.inner.is_null() {
+            return Err(RuntimeError::NullPointer("object"));
+        }
+
+        function(object.inner.cast(), field.inner.cast(), value);
+
+        Ok(())
     }
 
-    fn unbox_object(&self, _object: &UnityObject) -> Result<UnityObject, RuntimeError> {
-        Err(RuntimeError::NotImplemented("unbox_object"))
+    fn get_static_field_value(&self, field: &UnityField, value: *mut c_void) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_field_static_get_value
+            .ok_or(RuntimeError::MissingFunction("il2cpp_field_static_get_value"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        if value.is_null() {
+            return Err(RuntimeError::NullPointer("value"));
+        }
+
+        function(field.inner.cast(), value);
+
+        Ok(())
+    }
+
+    fn set_static_field_value(&self, field: &UnityField, value: *mut c_void) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_field_static_set_value
+            .ok_or(RuntimeError::MissingFunction("il2cpp_field_static_set_value"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        function(field.inner.cast(), value);
+
+        Ok(())
+    }
+
+    fn get_type_name(&self, ty: &UnityType) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_type_get_name
+            .ok_or(RuntimeError::MissingFunction("il2cpp_type_get_name"))?;
+
+        if ty.inner.is_null() {
+            return Err(RuntimeError::NullPointer("type"));
+        }
+
+        let name_c = function(ty.inner.cast());
+
+        if name_c.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_type_get_name"));
+        }
+
+        // freed before the utf-8 check, which may fail
+        let name = unsafe { CStr::from_ptr(name_c) }.to_str().map(str::to_string);
+
+        self.free(name_c.cast_mut().cast())?;
+
+        Ok(name?)
+    }
+
+    fn get_class_namespace(&self, class: &UnityClass) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_get_namespace
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_get_namespace"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        let namespace = function(class.inner.cast());
+
+        if namespace.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_class_get_namespace"));
+        }
+
+        let namespace = unsafe { CStr::from_ptr(namespace) }.to_str()?;
+
+        Ok(namespace.to_string())
+    }
+
+    fn get_class_parent(&self, class: &UnityClass) -> Result<Option<UnityClass>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_get_parent
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_get_parent"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        let parent = function(class.inner.cast());
+
+        match parent.is_null() {
+            false => Ok(Some(UnityClass {
+                inner: parent.cast(),
+            })),
+            true => Ok(None),
+        }
+    }
+
+    fn get_class_methods(&self, class: &UnityClass) -> Result<Vec<UnityMethod>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_get_methods
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_get_methods"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(collect_iter(|iter| function(class.inner.cast(), iter))
+            .into_iter()
+            .map(|item| UnityMethod { inner: item.cast() })
+            .collect())
+    }
+
+    fn get_class_fields(&self, class: &UnityClass) -> Result<Vec<UnityField>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_get_fields
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_get_fields"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(collect_iter(|iter| function(class.inner.cast(), iter))
+            .into_iter()
+            .map(|item| UnityField { inner: item.cast() })
+            .collect())
+    }
+
+    fn get_class_properties(&self, class: &UnityClass) -> Result<Vec<UnityProperty>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_get_properties
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_get_properties"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(collect_iter(|iter| function(class.inner.cast(), iter))
+            .into_iter()
+            .map(|item| UnityProperty { inner: item.cast() })
+            .collect())
+    }
+
+    fn get_class_nested_types(&self, class: &UnityClass) -> Result<Vec<UnityClass>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_get_nested_types
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_get_nested_types"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(collect_iter(|iter| function(class.inner.cast(), iter))
+            .into_iter()
+            .map(|item| UnityClass { inner: item.cast() })
+            .collect())
+    }
+
+    fn get_class_interfaces(&self, class: &UnityClass) -> Result<Vec<UnityClass>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_get_interfaces
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_get_interfaces"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(collect_iter(|iter| function(class.inner.cast(), iter))
+            .into_iter()
+            .map(|item| UnityClass { inner: item.cast() })
+            .collect())
+    }
+
+    fn get_method_class(&self, method: &UnityMethod) -> Result<UnityClass, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_method_get_class
+            .ok_or(RuntimeError::MissingFunction("il2cpp_method_get_class"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        let class = function(method.inner.cast());
+
+        if class.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_method_get_class"));
+        }
+
+        Ok(UnityClass {
+            inner: class.cast(),
+        })
+    }
+
+    fn get_method_param_count(&self, method: &UnityMethod) -> Result<usize, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_method_get_param_count
+            .ok_or(RuntimeError::MissingFunction("il2cpp_method_get_param_count"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        Ok(function(method.inner.cast()) as usize)
+    }
+
+    fn get_method_param_types(&self, method: &UnityMethod) -> Result<Vec<UnityType>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_method_get_param
+            .ok_or(RuntimeError::MissingFunction("il2cpp_method_get_param"))?;
+
+        let count = self.get_method_param_count(method)?;
+
+        (0..count as u32)
+            .map(|i| {
+                let ty = function(method.inner.cast(), i);
+
+                match ty.is_null() {
+                    false => Ok(UnityType { inner: ty.cast() }),
+                    true => Err(RuntimeError::ReturnedNull("il2cpp_method_get_param")),
+                }
+            })
+            .collect()
+    }
+
+    fn get_method_param_names(&self, method: &UnityMethod) -> Result<Vec<String>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_method_get_param_name
+            .ok_or(RuntimeError::MissingFunction("il2cpp_method_get_param_name"))?;
+
+        let count = self.get_method_param_count(method)?;
+
+        (0..count as u32)
+            .map(|i| {
+                let name = function(method.inner.cast(), i);
+
+                match name.is_null() {
+                    false => Ok(unsafe { CStr::from_ptr(name) }.to_str()?.to_string()),
+                    true => Err(RuntimeError::ReturnedNull("il2cpp_method_get_param_name")),
+                }
+            })
+            .collect()
+    }
+
+    fn get_method_return_type(&self, method: &UnityMethod) -> Result<UnityType, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_method_get_return_type
+            .ok_or(RuntimeError::MissingFunction("il2cpp_method_get_return_type"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        let ty = function(method.inner.cast());
+
+        if ty.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_method_get_return_type"));
+        }
+
+        Ok(UnityType { inner: ty.cast() })
+    }
+
+    fn get_method_flags(&self, method: &UnityMethod) -> Result<i32, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_method_get_flags
+            .ok_or(RuntimeError::MissingFunction("il2cpp_method_get_flags"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        let mut iflags: u32 = 0;
+
+        Ok(function(method.inner.cast(), &mut iflags) as i32)
+    }
+
+    fn get_method_object(&self, method: &UnityMethod) -> Result<UnityObject, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_method_get_object
+            .ok_or(RuntimeError::MissingFunction("il2cpp_method_get_object"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        let object = function(method.inner.cast(), std::ptr::null_mut());
+
+        if object.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_method_get_object"));
+        }
+
+        Ok(UnityObject {
+            inner: object.cast(),
+        })
+    }
+
+    fn get_object_class(&self, object: &UnityObject) -> Result<UnityClass, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_object_get_class
+            .ok_or(RuntimeError::MissingFunction("il2cpp_object_get_class"))?;
+
+        if object.inner.is_null() {
+            return Err(RuntimeError::NullPointer("object"));
+        }
+
+        let class = function(object.inner.cast());
+
+        if class.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_object_get_class"));
+        }
+
+        Ok(UnityClass {
+            inner: class.cast(),
+        })
+    }
+
+    fn get_array_length(&self, array: &UnityObject) -> Result<usize, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_array_length
+            .ok_or(RuntimeError::MissingFunction("il2cpp_array_length"))?;
+
+        if array.inner.is_null() {
+            return Err(RuntimeError::NullPointer("array"));
+        }
+
+        Ok(function(array.inner.cast()) as usize)
+    }
+
+    /// il2cpp image names carry the file extension, it's stripped to match mono
+    fn get_image_name(&self, image: &UnityImage) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_image_get_name
+            .ok_or(RuntimeError::MissingFunction("il2cpp_image_get_name"))?;
+
+        if image.inner.is_null() {
+            return Err(RuntimeError::NullPointer("image"));
+        }
+
+        let name = function(image.inner.cast());
+
+        if name.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_image_get_name"));
+        }
+
+        let name = unsafe { CStr::from_ptr(name) }.to_str()?;
+
+        Ok(name.trim_end_matches(".dll").to_string())
+    }
+
+    fn get_image_filename(&self, image: &UnityImage) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_image_get_filename
+            .ok_or(RuntimeError::MissingFunction("il2cpp_image_get_filename"))?;
+
+        if image.inner.is_null() {
+            return Err(RuntimeError::NullPointer("image"));
+        }
+
+        let name = function(image.inner.cast());
+
+        if name.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_image_get_filename"));
+        }
+
+        let name = unsafe { CStr::from_ptr(name) }.to_str()?;
+
+        Ok(name.to_string())
+    }
+
+    fn get_image_assembly(&self, image: &UnityImage) -> Result<UnityAssembly, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_image_get_assembly
+            .ok_or(RuntimeError::MissingFunction("il2cpp_image_get_assembly"))?;
+
+        if image.inner.is_null() {
+            return Err(RuntimeError::NullPointer("image"));
+        }
+
+        let assembly = function(image.inner.cast());
+
+        if assembly.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_image_get_assembly"));
+        }
+
+        Ok(UnityAssembly {
+            inner: assembly.cast(),
+        })
+    }
+
+    fn get_image_classes(&self, image: &UnityImage) -> Result<Vec<UnityClass>, RuntimeError> {
+        let get_count = &self
+            .exports
+            .clone()
+            .il2cpp_image_get_class_count
+            .ok_or(RuntimeError::MissingFunction("il2cpp_image_get_class_count"))?;
+
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_image_get_class
+            .ok_or(RuntimeError::MissingFunction("il2cpp_image_get_class"))?;
+
+        if image.inner.is_null() {
+            return Err(RuntimeError::NullPointer("image"));
+        }
+
+        let count = get_count(image.inner.cast());
+
+        Ok((0..count)
+            .map(|i| function(image.inner.cast(), i))
+            .filter(|class| !class.is_null())
+            .map(|class| UnityClass {
+                inner: class.cast(),
+            })
+            .collect())
+    }
+
+    /// il2cpp only hands out the utf16 buffer, so the conversion happens on our side
+    fn string_to_utf8(&self, string: &UnityString) -> Result<String, RuntimeError> {
+        let get_length = &self
+            .exports
+            .clone()
+            .il2cpp_string_length
+            .ok_or(RuntimeError::MissingFunction("il2cpp_string_length"))?;
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_string_chars
+            .ok_or(RuntimeError::MissingFunction("il2cpp_string_chars"))?;
+
+        if string.inner.is_null() {
+            return Err(RuntimeError::NullPointer("string"));
+        }
+
+        let length = get_length(string.inner.cast());
+        let chars = function(string.inner.cast());
+
+        if chars.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_string_chars"));
+        }
+
+        let chars = unsafe { std::slice::from_raw_parts(chars, length.max(0) as usize) };
+
+        String::from_utf16(chars).map_err(|e| RuntimeError::Passthrough(e.to_string()))
+    }
+
+    fn get_type_class(&self, ty: &UnityType) -> Result<UnityClass, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_from_type
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_from_type"))?;
+
+        if ty.inner.is_null() {
+            return Err(RuntimeError::NullPointer("type"));
+        }
+
+        let class = function(ty.inner.cast());
+
+        if class.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_class_from_type"));
+        }
+
+        Ok(UnityClass {
+            inner: class.cast(),
+        })
+    }
+
+    fn is_type_byref(&self, ty: &UnityType) -> Result<bool, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_type_is_byref
+            .ok_or(RuntimeError::MissingFunction("il2cpp_type_is_byref"))?;
+
+        if ty.inner.is_null() {
+            return Err(RuntimeError::NullPointer("type"));
+        }
+
+        Ok(function(ty.inner.cast()))
+    }
+
+    fn is_class_valuetype(&self, class: &UnityClass) -> Result<bool, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_is_valuetype
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_is_valuetype"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(function(class.inner.cast()))
+    }
+
+    fn is_class_enum(&self, class: &UnityClass) -> Result<bool, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_is_enum
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_is_enum"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(function(class.inner.cast()))
+    }
+
+    fn is_class_assignable_from(&self, class: &UnityClass, other: &UnityClass) -> Result<bool, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_is_assignable_from
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_is_assignable_from"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        if other.inner.is_null() {
+            return Err(RuntimeError::NullPointer("other"));
+        }
+
+        Ok(function(class.inner.cast(), other.inner.cast()))
+    }
+
+    fn get_class_value_size(&self, class: &UnityClass) -> Result<usize, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_class_value_size
+            .ok_or(RuntimeError::MissingFunction("il2cpp_class_value_size"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        let mut align = 0;
+
+        Ok(function(class.inner.cast(), &mut align) as usize)
+    }
+
+    fn get_assembly_version(&self, _assembly: &UnityAssembly) -> Result<String, RuntimeError> {
+        Err(RuntimeError::NotImplemented(
+            "il2cpp does not export assembly versions",
+        ))
+    }
+
+    fn get_image_guid(&self, _image: &UnityImage) -> Result<String, RuntimeError> {
+        Err(RuntimeError::NotImplemented(
+            "il2cpp does not export image guids",
+        ))
+    }
+    fn try_invoke_method(
+        &self,
+        method: &UnityMethod,
+        obj: Option<&UnityObject>,
+        params: Option<&mut Vec<*mut c_void>>,
+    ) -> Result<Option<UnityObject>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_runtime_invoke
+            .ok_or(RuntimeError::MissingFunction("il2cpp_runtime_invoke"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        let mut exc: *mut Il2CppObject = std::ptr::null_mut();
+        let object = match obj {
+            Some(obj) => obj.inner,
+            None => std::ptr::null_mut(),
+        };
+
+        let params = match params {
+            Some(params) if !params.is_empty() => params.as_mut_ptr(),
+            _ => std::ptr::null_mut(),
+        };
+
+        let result = function(method.inner.cast(), object.cast(), params, &mut exc);
+
+        if !exc.is_null() {
+            return Err(RuntimeError::ManagedException(self.format_exception(exc)));
+        }
+
+        match result.is_null() {
+            false => Ok(Some(UnityObject {
+                inner: result.cast(),
+            })),
+            true => Ok(None),
+        }
+    }
+
+    fn new_object(&self, class: &UnityClass) -> Result<UnityObject, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_object_new
+            .ok_or(RuntimeError::MissingFunction("il2cpp_object_new"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        let object = function(class.inner.cast());
+
+        if object.is_null() {
+            return Err(RuntimeError::ReturnedNull("il2cpp_object_new"));
+        }
+
+        Ok(UnityObject {
+            inner: object.cast(),
+        })
+    }
+
+    fn new_gc_handle(&self, object: &UnityObject, pinned: bool) -> Result<u32, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_gchandle_new
+            .ok_or(RuntimeError::MissingFunction("il2cpp_gchandle_new"))?;
+
+        if object.inner.is_null() {
+            return Err(RuntimeError::NullPointer("object"));
+        }
+
+        Ok(function(object.inner.cast(), pinned))
+    }
+
+    fn free_gc_handle(&self, handle: u32) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_gchandle_free
+            .ok_or(RuntimeError::MissingFunction("il2cpp_gchandle_free"))?;
+
+        function(handle);
+
+        Ok(())
+    }
+
+    fn get_class_attributes(&self, _class: &UnityClass) -> Result<Vec<UnityClass>, RuntimeError> {
+        Err(RuntimeError::NotImplemented(
+            "il2cpp does not export custom attributes",
+        ))
+    }
+
+    fn compile_method(&self, method: &UnityMethod) -> Result<MethodPointer, RuntimeError> {
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        // everything is compiled ahead of time, abstract methods and generic definitions have no code
+        let pointer = unsafe { (*method.inner.cast::<Il2CppMethod>()).method_pointer };
+
+        if pointer.is_null() {
+            return Err(RuntimeError::ReturnedNull("compile_method"));
+        }
+
+        Ok(pointer)
+    }
+
+    fn get_method_generic_arity(&self, method: &UnityMethod) -> Result<usize, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_method_is_generic
+            .ok_or(RuntimeError::MissingFunction("il2cpp_method_is_generic"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        if !function(method.inner.cast()) {
+            return Ok(0);
+        }
+
+        // nothing exports the generic container, so only generic definitions, which are
+        // rare, ask reflection for `MethodInfo.GetGenericArguments().Length`
+        let object = self.get_method_object(method)?;
+        let class = self.get_object_class(&object)?;
+        let get_args = self.get_method("GetGenericArguments", 0, &class)?;
+
+        match self.invoke_method(&get_args, Some(&object), None)? {
+            Some(array) => self.get_array_length(&array),
+            None => Ok(0),
+        }
+    }
+
+    fn is_method_inflated(&self, method: &UnityMethod) -> Result<bool, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .il2cpp_method_is_inflated
+            .ok_or(RuntimeError::MissingFunction("il2cpp_method_is_inflated"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        Ok(function(method.inner.cast()))
     }
 }
@@ -1,12 +1,18 @@
 //! TODO
 
+use std::ffi::c_void;
+
 #[derive(Debug)]
 #[repr(C)]
 pub struct Il2CppDomain {}
 
+/// a method, only the leading field of MethodInfo is mapped
 #[derive(Debug)]
 #[repr(C)]
-pub struct Il2CppMethod {}
+pub struct Il2CppMethod {
+    /// the native code pointer of the method
+    pub method_pointer: *mut c_void,
+}
 
 #[derive(Debug)]
 #[repr(C)]
@@ -19,3 +25,27 @@ pub struct Il2CppThread {}
 #[derive(Debug)]
 #[repr(C)]
 pub struct Il2CppString {}
+
+#[derive(Debug)]
+#[repr(C)]
+pub struct Il2CppAssembly {}
+
+#[derive(Debug)]
+#[repr(C)]
+pub struct Il2CppImage {}
+
+#[derive(Debug)]
+#[repr(C)]
+pub struct Il2CppClass {}
+
+#[derive(Debug)]
+#[repr(C)]
+pub struct Il2CppProperty {}
+
+#[derive(Debug)]
+#[repr(C)]
+pub struct Il2CppField {}
+
+#[derive(Debug)]
+#[repr(C)]
+pub struct Il2CppType {}
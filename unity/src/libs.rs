@@ -30,9 +30,9 @@ use thiserror::Error;
 /// possible library loading errors
 #[derive(Debug, Error)]
 pub enum LibError {
-    /// failed to load library
-    #[error("Failed to load library!")]
-    FailedToLoadLib,
+    /// failed to load library, with the loader's reason
+    #[error("Failed to load library: {0}")]
+    FailedToLoadLib(String),
 
     /// failed to get lib name
     #[error("Failed to get lib name!")]
@@ -48,6 +48,10 @@ pub enum LibError {
 
     #[error("Failed to create C-String")]
     FailedToCreateCString,
+
+    /// the library isn't loaded into the process
+    #[error("{0} is not loaded")]
+    NotLoaded(String),
 }
 
 /// a representation of a permanently loaded library
@@ -130,7 +134,7 @@ impl NativeLibrary {
 /// assert!(lib.is_ok());
 #[cfg(not(target_os = "windows"))]
 pub fn load_lib<P: AsRef<Path>>(path: P) -> Result<NativeLibrary, LibError> {
-    use std::ffi::CString;
+    use std::ffi::{CStr, CString};
 
     let path = path.as_ref();
 
@@ -141,7 +145,13 @@ pub fn load_lib<P: AsRef<Path>>(path: P) -> Result<NativeLibrary, LibError> {
     let lib = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_GLOBAL) };
 
     if lib.is_null() {
-        return Err(LibError::FailedToLoadLib);
+        let error = unsafe { libc::dlerror() };
+        let reason = match error.is_null() {
+            true => "unknown error".to_string(),
+            false => unsafe { CStr::from_ptr(error) }.to_string_lossy().to_string(),
+        };
+
+        return Err(LibError::FailedToLoadLib(reason));
     }
 
     let lib_name = path
@@ -198,7 +208,108 @@ pub fn load_lib<P: AsRef<Path>>(path: P) -> Result<NativeLibrary, LibError> {
     let lib = unsafe { LoadLibraryA(win_path.as_ptr()) };
 
     if lib.is_null() {
-        return Err(LibError::FailedToLoadLib);
+        return Err(LibError::FailedToLoadLib(std::io::Error::last_os_error().to_string()));
+    }
+
+    let lib_name = path
+        .file_name()
+        .ok_or(LibError::FailedToGetLibName)?
+        .to_str()
+        .ok_or(LibError::FailedToGetLibName)?
+        .to_string();
+
+    Ok(NativeLibrary {
+        name: lib_name,
+        path: path.to_path_buf(),
+        handle: lib.cast(),
+    })
+}
+
+/// opens a library that is already loaded, without ever loading it
+///
+/// # Arguments
+///
+/// * `name` - the file name of the library, like `UnityPlayer.so`, or its path
+///
+/// # Errors
+///
+/// * `LibError::NotLoaded` - if the library isn't loaded
+///
+/// # Examples
+///
+/// ```no_run
+/// use unity_rs::libs::open_loaded;
+///
+/// let libc = open_loaded("libc.so.6")?;
+/// let puts = libc.sym::<extern "C" fn(*const i8) -> i32>("puts")?;
+/// # Ok::<(), unity_rs::libs::LibError>(())
+/// ```
+#[cfg(not(target_os = "windows"))]
+pub fn open_loaded<P: AsRef<Path>>(name: P) -> Result<NativeLibrary, LibError> {
+    use std::ffi::CString;
+
+    let path = name.as_ref();
+
+    let path_string = path.to_str().ok_or(LibError::FailedToGetLibPath)?;
+
+    let c_path = CString::new(path_string).map_err(|_| LibError::FailedToCreateCString)?;
+
+    // RTLD_NOLOAD only hands out a handle if the library is loaded already
+    let lib = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_NOLOAD) };
+
+    if lib.is_null() {
+        return Err(LibError::NotLoaded(path_string.to_string()));
+    }
+
+    let lib_name = path
+        .file_name()
+        .ok_or(LibError::FailedToGetLibName)?
+        .to_str()
+        .ok_or(LibError::FailedToGetLibName)?
+        .to_string();
+
+    Ok(NativeLibrary {
+        name: lib_name,
+        path: path.to_path_buf(),
+        handle: lib,
+    })
+}
+
+/// opens a library that is already loaded, without ever loading it
+///
+/// # Arguments
+///
+/// * `name` - the file name of the library, like `UnityPlayer.dll`, or its path
+///
+/// # Errors
+///
+/// * `LibError::NotLoaded` - if the library isn't loaded
+///
+/// # Examples
+///
+/// ```no_run
+/// use unity_rs::libs::open_loaded;
+///
+/// let kernel32 = open_loaded("kernel32.dll")?;
+/// let sleep = kernel32.sym::<extern "system" fn(u32)>("Sleep")?;
+/// # Ok::<(), unity_rs::libs::LibError>(())
+/// ```
+#[cfg(target_os = "windows")]
+pub fn open_loaded<P: AsRef<Path>>(name: P) -> Result<NativeLibrary, LibError> {
+    use std::ffi::CString;
+
+    let path = name.as_ref();
+
+    use winapi::um::libloaderapi::GetModuleHandleA;
+
+    let path_string = path.to_str().ok_or(LibError::FailedToGetLibPath)?;
+    let win_path = CString::new(path_string).map_err(|_| LibError::FailedToCreateCString)?;
+
+    // unlike LoadLibrary, this never loads anything, nor takes a reference
+    let lib = unsafe { GetModuleHandleA(win_path.as_ptr()) };
+
+    if lib.is_null() {
+        return Err(LibError::NotLoaded(path_string.to_string()));
     }
 
     let lib_name = path
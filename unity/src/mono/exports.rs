@@ -6,7 +6,8 @@ use crate::{libs::{LibError, NativeLibrary, NativeMethod}, utils::libs::get_func
 
 use super::types::{
     AssemblyName, MonoAssembly, MonoClass, MonoDomain, MonoImage, MonoMethod, MonoObject,
-    MonoString, MonoThread, MonoProperty,
+    MonoString, MonoThread, MonoProperty, MonoClassField, MonoType, MonoVTable,
+    MonoMethodSignature, MonoCustomAttrInfo, MonoTableInfo,
 };
 
 type GFunc = extern "C" fn(*mut MonoAssembly, *mut c_void);
@@ -60,12 +61,67 @@ pub struct MonoExports {
     pub mono_property_get_set_method: Option<NativeMethod<fn(*mut MonoProperty) -> *mut MonoMethod>>,
     pub mono_method_get_unmanaged_thunk: Option<NativeMethod<fn(*mut MonoMethod) -> *mut c_void>>,
     pub mono_object_unbox: Option<NativeMethod<fn(*mut MonoObject) -> *mut c_void>>,
+    pub mono_value_box: Option<NativeMethod<fn(*mut MonoDomain, *mut MonoClass, *mut c_void) -> *mut MonoObject>>,
+    pub mono_class_get_field_from_name: Option<NativeMethod<fn(*mut MonoClass, *const c_char) -> *mut MonoClassField>>,
+    pub mono_field_get_name: Option<NativeMethod<fn(*mut MonoClassField) -> *const c_char>>,
+    pub mono_field_get_type: Option<NativeMethod<fn(*mut MonoClassField) -> *mut MonoType>>,
+    pub mono_field_get_offset: Option<NativeMethod<fn(*mut MonoClassField) -> u32>>,
+    pub mono_field_get_parent: Option<NativeMethod<fn(*mut MonoClassField) -> *mut MonoClass>>,
+    pub mono_field_get_flags: Option<NativeMethod<fn(*mut MonoClassField) -> u32>>,
+    pub mono_field_get_value: Option<NativeMethod<fn(*mut MonoObject, *mut MonoClassField, *mut c_void)>>,
+    pub mono_field_set_value: Option<NativeMethod<fn(*mut MonoObject, *mut MonoClassField, *mut c_void)>>,
+    pub mono_field_static_get_value: Option<NativeMethod<fn(*mut MonoVTable, *mut MonoClassField, *mut c_void)>>,
+    pub mono_field_static_set_value: Option<NativeMethod<fn(*mut MonoVTable, *mut MonoClassField, *mut c_void)>>,
+    pub mono_class_vtable: Option<NativeMethod<fn(*mut MonoDomain, *mut MonoClass) -> *mut MonoVTable>>,
+    pub mono_type_get_name: Option<NativeMethod<fn(*mut MonoType) -> *const c_char>>,
+    pub mono_free: Option<NativeMethod<fn(*mut c_void)>>,
+    pub mono_class_get_namespace: Option<NativeMethod<fn(*mut MonoClass) -> *const c_char>>,
+    pub mono_class_get_parent: Option<NativeMethod<fn(*mut MonoClass) -> *mut MonoClass>>,
+    pub mono_class_get_methods: Option<NativeMethod<fn(*mut MonoClass, *mut *mut c_void) -> *mut MonoMethod>>,
+    pub mono_class_get_fields: Option<NativeMethod<fn(*mut MonoClass, *mut *mut c_void) -> *mut MonoClassField>>,
+    pub mono_class_get_properties: Option<NativeMethod<fn(*mut MonoClass, *mut *mut c_void) -> *mut MonoProperty>>,
+    pub mono_class_get_nested_types: Option<NativeMethod<fn(*mut MonoClass, *mut *mut c_void) -> *mut MonoClass>>,
+    pub mono_class_get_interfaces: Option<NativeMethod<fn(*mut MonoClass, *mut *mut c_void) -> *mut MonoClass>>,
+    pub mono_method_get_class: Option<NativeMethod<fn(*mut MonoMethod) -> *mut MonoClass>>,
+    pub mono_method_signature: Option<NativeMethod<fn(*mut MonoMethod) -> *mut MonoMethodSignature>>,
+    pub mono_method_get_param_names: Option<NativeMethod<fn(*mut MonoMethod, *mut *const c_char)>>,
+    pub mono_method_get_flags: Option<NativeMethod<fn(*mut MonoMethod, *mut u32) -> u32>>,
+    pub mono_method_get_object: Option<NativeMethod<fn(*mut MonoDomain, *mut MonoMethod, *mut MonoClass) -> *mut MonoObject>>,
+    pub mono_signature_get_params: Option<NativeMethod<fn(*mut MonoMethodSignature, *mut *mut c_void) -> *mut MonoType>>,
+    pub mono_signature_get_param_count: Option<NativeMethod<fn(*mut MonoMethodSignature) -> u32>>,
+    pub mono_signature_get_return_type: Option<NativeMethod<fn(*mut MonoMethodSignature) -> *mut MonoType>>,
+    pub mono_object_get_class: Option<NativeMethod<fn(*mut MonoObject) -> *mut MonoClass>>,
+    pub mono_array_length: Option<NativeMethod<fn(*mut MonoObject) -> usize>>,
+    pub mono_image_get_name: Option<NativeMethod<fn(*mut MonoImage) -> *const c_char>>,
+    pub mono_image_get_filename: Option<NativeMethod<fn(*mut MonoImage) -> *const c_char>>,
+    pub mono_image_get_assembly: Option<NativeMethod<fn(*mut MonoImage) -> *mut MonoAssembly>>,
+    pub mono_image_get_table_rows: Option<NativeMethod<fn(*mut MonoImage, c_int) -> c_int>>,
+    pub mono_class_get: Option<NativeMethod<fn(*mut MonoImage, u32) -> *mut MonoClass>>,
+    pub mono_class_from_mono_type: Option<NativeMethod<fn(*mut MonoType) -> *mut MonoClass>>,
+    pub mono_type_is_byref: Option<NativeMethod<fn(*mut MonoType) -> c_int>>,
+    pub mono_class_is_valuetype: Option<NativeMethod<fn(*mut MonoClass) -> c_int>>,
+    pub mono_class_is_enum: Option<NativeMethod<fn(*mut MonoClass) -> c_int>>,
+    pub mono_class_is_assignable_from: Option<NativeMethod<fn(*mut MonoClass, *mut MonoClass) -> c_int>>,
+    pub mono_class_value_size: Option<NativeMethod<fn(*mut MonoClass, *mut u32) -> c_int>>,
+    pub mono_image_get_guid: Option<NativeMethod<fn(*mut MonoImage) -> *const c_char>>,
+    pub mono_object_new: Option<NativeMethod<fn(*mut MonoDomain, *mut MonoClass) -> *mut MonoObject>>,
+    pub mono_gchandle_new: Option<NativeMethod<fn(*mut MonoObject, c_int) -> u32>>,
+    pub mono_gchandle_free: Option<NativeMethod<fn(u32)>>,
+    pub mono_custom_attrs_from_class: Option<NativeMethod<fn(*mut MonoClass) -> *mut MonoCustomAttrInfo>>,
+    pub mono_custom_attrs_free: Option<NativeMethod<fn(*mut MonoCustomAttrInfo)>>,
+    pub mono_compile_method: Option<NativeMethod<fn(*mut MonoMethod) -> *mut c_void>>,
+    pub mono_method_get_token: Option<NativeMethod<fn(*mut MonoMethod) -> u32>>,
+    pub mono_method_get_context: Option<NativeMethod<fn(*mut MonoMethod) -> *mut c_void>>,
+    pub mono_class_get_image: Option<NativeMethod<fn(*mut MonoClass) -> *mut MonoImage>>,
+    pub mono_image_get_table_info: Option<NativeMethod<fn(*mut MonoImage, c_int) -> *const MonoTableInfo>>,
+    pub mono_table_info_get_rows: Option<NativeMethod<fn(*const MonoTableInfo) -> c_int>>,
+    pub mono_metadata_decode_row_col: Option<NativeMethod<fn(*const MonoTableInfo, c_int, u32) -> u32>>,
 }
 
 impl MonoExports {
     pub fn new(lib: &NativeLibrary) -> Result<Self, LibError> {
         Ok(MonoExports {
-            mono_jit_init_version: get_function_option(&lib,  "mono_jit_init_version")?,
+            mono_jit_init_version: get_function_option(lib,  "mono_jit_init_version")?,
             mono_debug_domain_create: {
                 // probably not present on old mono
                 let res = lib.sym("mono_debug_domain_create");
@@ -74,34 +130,89 @@ impl MonoExports {
                     false => Some(res.unwrap()),
                 }
             },
-            mono_string_new: get_function_option(&lib,  "mono_string_new")?,
-            mono_runtime_invoke: get_function_option(&lib,  "mono_runtime_invoke")?,
-            mono_string_to_utf8: get_function_option(&lib,  "mono_string_to_utf8")?,
-            mono_thread_current: get_function_option(&lib,  "mono_thread_current")?,
-            mono_thread_attach: get_function_option(&lib,  "mono_thread_attach")?,
-            mono_get_root_domain: get_function_option(&lib,  "mono_get_root_domain")?,
-            mono_class_from_name: get_function_option(&lib,  "mono_class_from_name")?,
-            mono_method_get_name: get_function_option(&lib,  "mono_method_get_name")?,
-            mono_thread_set_main: get_function_option(&lib,  "mono_thread_set_main")?,
-            mono_object_to_string: get_function_option(&lib,  "mono_object_to_string")?,
-            mono_add_internal_call: get_function_option(&lib,  "mono_add_internal_call")?,
-            mono_domain_set_config: get_function_option(&lib,  "mono_domain_set_config")?,
-            mono_assembly_get_image: get_function_option(&lib,  "mono_assembly_get_image")?,
-            mono_assembly_get_object: get_function_option(&lib,  "mono_assembly_get_object")?,
-            mono_domain_assembly_open: get_function_option(&lib,  "mono_domain_assembly_open")?,
-            mono_install_assembly_load_hook: get_function_option(&lib,  "mono_install_assembly_load_hook")?,
-            mono_class_get_method_from_name: get_function_option(&lib,  "mono_class_get_method_from_name")?,
-            mono_install_assembly_search_hook: get_function_option(&lib,  "mono_install_assembly_search_hook")?,
-            mono_install_assembly_preload_hook: get_function_option(&lib,  "mono_install_assembly_preload_hook")?,
-            mono_assembly_foreach: get_function_option(&lib,  "mono_assembly_foreach")?,
-            mono_assembly_get_name: get_function_option(&lib,  "mono_assembly_get_name")?,
-            mono_class_get_name: get_function_option(&lib,  "mono_class_get_name")?,
-            mono_class_get_property_from_name: get_function_option(&lib,  "mono_class_get_property_from_name")?,
-            mono_property_get_name: get_function_option(&lib,  "mono_property_get_name")?,
-            mono_property_get_get_method: get_function_option(&lib,  "mono_property_get_get_method")?,
-            mono_property_get_set_method: get_function_option(&lib,  "mono_property_get_set_method")?,
-            mono_method_get_unmanaged_thunk: get_function_option(&lib,  "mono_method_get_unmanaged_thunk")?,
-            mono_object_unbox: get_function_option(&lib,  "mono_object_unbox")?,
+            mono_string_new: get_function_option(lib,  "mono_string_new")?,
+            mono_runtime_invoke: get_function_option(lib,  "mono_runtime_invoke")?,
+            mono_string_to_utf8: get_function_option(lib,  "mono_string_to_utf8")?,
+            mono_thread_current: get_function_option(lib,  "mono_thread_current")?,
+            mono_thread_attach: get_function_option(lib,  "mono_thread_attach")?,
+            mono_get_root_domain: get_function_option(lib,  "mono_get_root_domain")?,
+            mono_class_from_name: get_function_option(lib,  "mono_class_from_name")?,
+            mono_method_get_name: get_function_option(lib,  "mono_method_get_name")?,
+            mono_thread_set_main: get_function_option(lib,  "mono_thread_set_main")?,
+            mono_object_to_string: get_function_option(lib,  "mono_object_to_string")?,
+            mono_add_internal_call: get_function_option(lib,  "mono_add_internal_call")?,
+            mono_domain_set_config: get_function_option(lib,  "mono_domain_set_config")?,
+            mono_assembly_get_image: get_function_option(lib,  "mono_assembly_get_image")?,
+            mono_assembly_get_object: get_function_option(lib,  "mono_assembly_get_object")?,
+            mono_domain_assembly_open: get_function_option(lib,  "mono_domain_assembly_open")?,
+            mono_install_assembly_load_hook: get_function_option(lib,  "mono_install_assembly_load_hook")?,
+            mono_class_get_method_from_name: get_function_option(lib,  "mono_class_get_method_from_name")?,
+            mono_install_assembly_search_hook: get_function_option(lib,  "mono_install_assembly_search_hook")?,
+            mono_install_assembly_preload_hook: get_function_option(lib,  "mono_install_assembly_preload_hook")?,
+            mono_assembly_foreach: get_function_option(lib,  "mono_assembly_foreach")?,
+            mono_assembly_get_name: get_function_option(lib,  "mono_assembly_get_name")?,
+            mono_class_get_name: get_function_option(lib,  "mono_class_get_name")?,
+            mono_class_get_property_from_name: get_function_option(lib,  "mono_class_get_property_from_name")?,
+            mono_property_get_name: get_function_option(lib,  "mono_property_get_name")?,
+            mono_property_get_get_method: get_function_option(lib,  "mono_property_get_get_method")?,
+            mono_property_get_set_method: get_function_option(lib,  "mono_property_get_set_method")?,
+            mono_method_get_unmanaged_thunk: get_function_option(lib,  "mono_method_get_unmanaged_thunk")?,
+            mono_object_unbox: get_function_option(lib,  "mono_object_unbox")?,
+            mono_value_box: get_function_option(lib,  "mono_value_box")?,
+            mono_class_get_field_from_name: get_function_option(lib,  "mono_class_get_field_from_name")?,
+            mono_field_get_name: get_function_option(lib,  "mono_field_get_name")?,
+            mono_field_get_type: get_function_option(lib,  "mono_field_get_type")?,
+            mono_field_get_offset: get_function_option(lib,  "mono_field_get_offset")?,
+            mono_field_get_parent: get_function_option(lib,  "mono_field_get_parent")?,
+            mono_field_get_flags: get_function_option(lib,  "mono_field_get_flags")?,
+            mono_field_get_value: get_function_option(lib,  "mono_field_get_value")?,
+            mono_field_set_value: get_function_option(lib,  "mono_field_set_value")?,
+            mono_field_static_get_value: get_function_option(lib,  "mono_field_static_get_value")?,
+            mono_field_static_set_value: get_function_option(lib,  "mono_field_static_set_value")?,
+            mono_class_vtable: get_function_option(lib,  "mono_class_vtable")?,
+            mono_type_get_name: get_function_option(lib,  "mono_type_get_name")?,
+            mono_free: get_function_option(lib,  "mono_free")?,
+            mono_class_get_namespace: get_function_option(lib,  "mono_class_get_namespace")?,
+            mono_class_get_parent: get_function_option(lib,  "mono_class_get_parent")?,
+            mono_class_get_methods: get_function_option(lib,  "mono_class_get_methods")?,
+            mono_class_get_fields: get_function_option(lib,  "mono_class_get_fields")?,
+            mono_class_get_properties: get_function_option(lib,  "mono_class_get_properties")?,
+            mono_class_get_nested_types: get_function_option(lib,  "mono_class_get_nested_types")?,
+            mono_class_get_interfaces: get_function_option(lib,  "mono_class_get_interfaces")?,
+            mono_method_get_class: get_function_option(lib,  "mono_method_get_class")?,
+            mono_method_signature: get_function_option(lib,  "mono_method_signature")?,
+            mono_method_get_param_names: get_function_option(lib,  "mono_method_get_param_names")?,
+            mono_method_get_flags: get_function_option(lib,  "mono_method_get_flags")?,
+            mono_method_get_object: get_function_option(lib,  "mono_method_get_object")?,
+            mono_signature_get_params: get_function_option(lib,  "mono_signature_get_params")?,
+            mono_signature_get_param_count: get_function_option(lib,  "mono_signature_get_param_count")?,
+            mono_signature_get_return_type: get_function_option(lib,  "mono_signature_get_return_type")?,
+            mono_object_get_class: get_function_option(lib,  "mono_object_get_class")?,
+            mono_array_length: get_function_option(lib,  "mono_array_length")?,
+            mono_image_get_name: get_function_option(lib,  "mono_image_get_name")?,
+            mono_image_get_filename: get_function_option(lib,  "mono_image_get_filename")?,
+            mono_image_get_assembly: get_function_option(lib,  "mono_image_get_assembly")?,
+            mono_image_get_table_rows: get_function_option(lib,  "mono_image_get_table_rows")?,
+            mono_class_get: get_function_option(lib,  "mono_class_get")?,
+            mono_class_from_mono_type: get_function_option(lib,  "mono_class_from_mono_type")?,
+            mono_type_is_byref: get_function_option(lib,  "mono_type_is_byref")?,
+            mono_class_is_valuetype: get_function_option(lib,  "mono_class_is_valuetype")?,
+            mono_class_is_enum: get_function_option(lib,  "mono_class_is_enum")?,
+            mono_class_is_assignable_from: get_function_option(lib,  "mono_class_is_assignable_from")?,
+            mono_class_value_size: get_function_option(lib,  "mono_class_value_size")?,
+            mono_image_get_guid: get_function_option(lib,  "mono_image_get_guid")?,
+            mono_object_new: get_function_option(lib,  "mono_object_new")?,
+            mono_gchandle_new: get_function_option(lib,  "mono_gchandle_new")?,
+            mono_gchandle_free: get_function_option(lib,  "mono_gchandle_free")?,
+            mono_custom_attrs_from_class: get_function_option(lib,  "mono_custom_attrs_from_class")?,
+            mono_custom_attrs_free: get_function_option(lib,  "mono_custom_attrs_free")?,
+            mono_compile_method: get_function_option(lib,  "mono_compile_method")?,
+            mono_method_get_token: get_function_option(lib,  "mono_method_get_token")?,
+            mono_method_get_context: get_function_option(lib,  "mono_method_get_context")?,
+            mono_class_get_image: get_function_option(lib,  "mono_class_get_image")?,
+            mono_image_get_table_info: get_function_option(lib,  "mono_image_get_table_info")?,
+            mono_table_info_get_rows: get_function_option(lib,  "mono_table_info_get_rows")?,
+            mono_metadata_decode_row_col: get_function_option(lib,  "mono_metadata_decode_row_col")?,
         })
     }
 }
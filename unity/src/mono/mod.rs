@@ -1,11 +1,11 @@
 //! TODO
 
 use std::{
+    cmp::Ordering,
     error,
-    ffi::{c_void, CStr, CString},
+    ffi::{c_char, c_int, c_void, CStr, CString},
     fmt::{self, Display},
     path::PathBuf,
-    ptr::addr_of_mut,
 };
 
 use crate::{
@@ -17,16 +17,19 @@ use crate::{
         method::{MethodPointer, UnityMethod},
         object::UnityObject,
         property::UnityProperty,
+        field::UnityField,
+        ty::UnityType,
         string::UnityString,
         thread::UnityThread,
     },
     libs::{self, NativeLibrary, NativeMethod},
     runtime::{Runtime, RuntimeError, RuntimeType},
+    utils::iter::collect_iter,
 };
 
 use self::{
     exports::MonoExports,
-    types::{MonoAssembly, MonoObject},
+    types::{MonoAssembly, MonoMethodSignature, MonoObject, MonoVTable},
 };
 
 pub mod exports;
@@ -53,6 +56,17 @@ impl Display for AssemblyHookType {
     }
 }
 
+/// the index of the TypeDef metadata table
+const MONO_TABLE_TYPEDEF: i32 = 2;
+/// the token prefix of TypeDef rows
+const MONO_TOKEN_TYPE_DEF: u32 = 0x02000000;
+/// the index of the GenericParam metadata table
+const MONO_TABLE_GENERICPARAM: i32 = 0x2a;
+/// the column of a GenericParam row that holds its owner
+const MONO_GENERICPARAM_OWNER: u32 = 2;
+/// the tag of a TypeOrMethodDef coded index pointing at a MethodDef
+const MONO_TYPEORMETHOD_METHOD: u32 = 1;
+
 #[derive(Debug, Clone)]
 pub struct Mono {
     pub is_old: bool,
@@ -89,6 +103,84 @@ impl Mono {
 
         Ok(mono)
     }
+
+    /// frees memory allocated by mono, e.g. strings returned by `mono_type_get_name`
+    pub fn free(&self, ptr: *mut c_void) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_free
+            .ok_or(RuntimeError::MissingFunction("mono_free"))?;
+
+        if ptr.is_null() {
+            return Err(RuntimeError::NullPointer("ptr"));
+        }
+
+        function(ptr);
+
+        Ok(())
+    }
+
+    /// the message and stack trace of a managed exception, as `ToString` formats them
+    fn format_exception(&self, exc: *mut MonoObject) -> String {
+        let Some(function) = self.exports.clone().mono_object_to_string else {
+            return "unknown exception".to_string();
+        };
+
+        // an exception thrown by ToString itself is ignored
+        let mut inner: *mut MonoObject = std::ptr::null_mut();
+        let string = function(exc, &mut inner);
+
+        if string.is_null() || !inner.is_null() {
+            return "unknown exception".to_string();
+        }
+
+        self.string_to_utf8(&UnityString {
+            inner: string.cast(),
+        })
+        .unwrap_or_else(|_| "unknown exception".to_string())
+    }
+
+    /// parameter and return types are read off the signature on mono
+    fn get_method_signature(&self, method: &UnityMethod) -> Result<*mut MonoMethodSignature, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_method_signature
+            .ok_or(RuntimeError::MissingFunction("mono_method_signature"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        let signature = function(method.inner.cast());
+
+        if signature.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_method_signature"));
+        }
+
+        Ok(signature)
+    }
+
+    /// static fields live in the vtable of their parent class, which is per domain
+    fn get_field_vtable(&self, field: &UnityField) -> Result<*mut MonoVTable, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_vtable
+            .ok_or(RuntimeError::MissingFunction("mono_class_vtable"))?;
+
+        let class = self.get_field_parent(field)?;
+        let domain = self.get_domain()?;
+
+        let vtable = function(domain.inner.cast(), class.inner.cast());
+
+        if vtable.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_class_vtable"));
+        }
+
+        Ok(vtable)
+    }
 }
 
 impl Runtime for Mono {
@@ -262,10 +354,7 @@ impl Runtime for Mono {
     }
 
     fn new_string(&self, name: &str) -> Result<UnityString, RuntimeError> {
-        if name.is_empty() {
-            return Err(RuntimeError::EmptyString);
-        }
-
+        // empty strings are fine, unlike the names the other lookups take
         let native_str = CString::new(name)?;
 
         self.string_from_raw(native_str.as_ptr())
@@ -314,8 +403,8 @@ impl Runtime for Mono {
         };
 
         let params = match params {
-            Some(params) => addr_of_mut!(params[0]),
-            None => std::ptr::null_mut(),
+            Some(params) if !params.is_empty() => params.as_mut_ptr(),
+            _ => std::ptr::null_mut(),
         };
 
         let result = function(
@@ -666,6 +755,1080 @@ impl Runtime for Mono {
             inner: object.cast(),
         })
     }
+
+    fn box_value(&self, class: &UnityClass, value: *mut c_void) -> Result<UnityObject, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_value_box
+            .ok_or(RuntimeError::MissingFunction("mono_value_box"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        if value.is_null() {
+            return Err(RuntimeError::NullPointer("value"));
+        }
+
+        let object = function(self.get_domain()?.inner.cast(), class.inner.cast(), value);
+
+        if object.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_value_box"));
+        }
+
+        Ok(UnityObject {
+            inner: object.cast(),
+        })
+    }
+
+    fn get_field(&self, class: &UnityClass, name: &str) -> Result<UnityField, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_get_field_from_name
+            .ok_or(RuntimeError::MissingFunction(
+                "mono_class_get_field_from_name",
+            ))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        if name.is_empty() {
+            return Err(RuntimeError::EmptyString);
+        }
+
+        let field = function(class.inner.cast(), CString::new(name)?.as_ptr());
+
+        if field.is_null() {
+            return Err(RuntimeError::ReturnedNull(
+                "mono_class_get_field_from_name",
+            ));
+        }
+
+        Ok(UnityField {
+            inner: field.cast(),
+        })
+    }
+
+    fn get_field_name(&self, field: &UnityField) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_field_get_name
+            .ok_or(RuntimeError::MissingFunction("mono_field_get_name"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        let name = function(field.inner.cast());
+
+        if name.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_field_get_name"));
+        }
+
+        let name = unsafe { CStr::from_ptr(name) }.to_str()?;
+
+        Ok(name.to_string())
+    }
+
+    fn get_field_type(&self, field: &UnityField) -> Result<UnityType, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_field_get_type
+            .ok_or(RuntimeError::MissingFunction("mono_field_get_type"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        let ty = function(field.inner.cast());
+
+        if ty.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_field_get_type"));
+        }
+
+        Ok(UnityType { inner: ty.cast() })
+    }
+
+    fn get_field_offset(&self, field: &UnityField) -> Result<usize, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_field_get_offset
+            .ok_or(RuntimeError::MissingFunction("mono_field_get_offset"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        let offset = function(field.inner.cast());
+
+        Ok(offset as usize)
+    }
+
+    fn get_field_parent(&self, field: &UnityField) -> Result<UnityClass, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_field_get_parent
+            .ok_or(RuntimeError::MissingFunction("mono_field_get_parent"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        let class = function(field.inner.cast());
+
+        if class.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_field_get_parent"));
+        }
+
+        Ok(UnityClass {
+            inner: class.cast(),
+        })
+    }
+
+    fn get_field_flags(&self, field: &UnityField) -> Result<i32, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_field_get_flags
+            .ok_or(RuntimeError::MissingFunction("mono_field_get_flags"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        let flags = function(field.inner.cast());
+
+        Ok(flags as i32)
+    }
+
+    fn get_field_value(
+        &self,
+        field: &UnityField,
+        object: &UnityObject,
+        value: *mut c_void,
+    ) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_field_get_value
+            .ok_or(RuntimeError::MissingFunction("mono_field_get_value"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        if object.inner.is_null() {
+            return Err(RuntimeError::NullPointer("object"));
+        }
+
+        if value.is_null() {
+            return Err(RuntimeError::NullPointer("value"));
+        }
+
+        function(object.inner.cast(), field.inner.cast(), value);
+
+        Ok(())
+    }
+
+    fn set_field_value(
+        &self,
+        field: &UnityField,
+        object: &UnityObject,
+        value: *mut c_void,
+    ) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_field_set_value
+            .ok_or(RuntimeError::MissingFunction("mono_field_set_value"))?;
+
+        if field.inner.is_null() {
+            return Err(RuntimeError::NullPointer("field"));
+        }
+
+        if object.inner.is_null() {
+            return Err(RuntimeError::NullPointer("object"));
+        }
+
+        function(object.inner.cast(), field.inner.cast(), value);
+
+        Ok(())
+    }
+
+    fn get_static_field_value(&self, field: &UnityField, value: *mut c_void) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_field_static_get_value
+            .ok_or(RuntimeError::MissingFunction("mono_field_static_get_value"))?;
+
+        if value.is_null() {
+            return Err(RuntimeError::NullPointer("value"));
+        }
+
+        let vtable = self.get_field_vtable(field)?;
+
+        function(vtable, field.inner.cast(), value);
+
+        Ok(())
+    }
+
+    fn set_static_field_value(&self, field: &UnityField, value: *mut c_void) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_field_static_set_value
+            .ok_or(RuntimeError::MissingFunction("mono_field_static_set_value"))?;
+
+        let vtable = self.get_field_vtable(field)?;
+
+        function(vtable, field.inner.cast(), value);
+
+        Ok(())
+    }
+
+    fn get_type_name(&self, ty: &UnityType) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_type_get_name
+            .ok_or(RuntimeError::MissingFunction("mono_type_get_name"))?;
+
+        if ty.inner.is_null() {
+            return Err(RuntimeError::NullPointer("type"));
+        }
+
+        let name_c = function(ty.inner.cast());
+
+        if name_c.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_type_get_name"));
+        }
+
+        // freed before the utf-8 check, which may fail
+        let name = unsafe { CStr::from_ptr(name_c) }.to_str().map(str::to_string);
+
+        self.free(name_c.cast_mut().cast())?;
+
+        Ok(name?)
+    }
+
+    fn get_class_namespace(&self, class: &UnityClass) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_get_namespace
+            .ok_or(RuntimeError::MissingFunction("mono_class_get_namespace"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        let namespace = function(class.inner.cast());
+
+        if namespace.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_class_get_namespace"));
+        }
+
+        let namespace = unsafe { CStr::from_ptr(namespace) }.to_str()?;
+
+        Ok(namespace.to_string())
+    }
+
+    fn get_class_parent(&self, class: &UnityClass) -> Result<Option<UnityClass>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_get_parent
+            .ok_or(RuntimeError::MissingFunction("mono_class_get_parent"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        let parent = function(class.inner.cast());
+
+        match parent.is_null() {
+            false => Ok(Some(UnityClass {
+                inner: parent.cast(),
+            })),
+            true => Ok(None),
+        }
+    }
+
+    fn get_class_methods(&self, class: &UnityClass) -> Result<Vec<UnityMethod>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_get_methods
+            .ok_or(RuntimeError::MissingFunction("mono_class_get_methods"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(collect_iter(|iter| function(class.inner.cast(), iter))
+            .into_iter()
+            .map(|item| UnityMethod { inner: item.cast() })
+            .collect())
+    }
+
+    fn get_class_fields(&self, class: &UnityClass) -> Result<Vec<UnityField>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_get_fields
+            .ok_or(RuntimeError::MissingFunction("mono_class_get_fields"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(collect_iter(|iter| function(class.inner.cast(), iter))
+            .into_iter()
+            .map(|item| UnityField { inner: item.cast() })
+            .collect())
+    }
+
+    fn get_class_properties(&self, class: &UnityClass) -> Result<Vec<UnityProperty>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_get_properties
+            .ok_or(RuntimeError::MissingFunction("mono_class_get_properties"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(collect_iter(|iter| function(class.inner.cast(), iter))
+            .into_iter()
+            .map(|item| UnityProperty { inner: item.cast() })
+            .collect())
+    }
+
+    fn get_class_nested_types(&self, class: &UnityClass) -> Result<Vec<UnityClass>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_get_nested_types
+            .ok_or(RuntimeError::MissingFunction("mono_class_get_nested_types"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(collect_iter(|iter| function(class.inner.cast(), iter))
+            .into_iter()
+            .map(|item| UnityClass { inner: item.cast() })
+            .collect())
+    }
+
+    fn get_class_interfaces(&self, class: &UnityClass) -> Result<Vec<UnityClass>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_get_interfaces
+            .ok_or(RuntimeError::MissingFunction("mono_class_get_interfaces"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(collect_iter(|iter| function(class.inner.cast(), iter))
+            .into_iter()
+            .map(|item| UnityClass { inner: item.cast() })
+            .collect())
+    }
+
+    fn get_method_class(&self, method: &UnityMethod) -> Result<UnityClass, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_method_get_class
+            .ok_or(RuntimeError::MissingFunction("mono_method_get_class"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        let class = function(method.inner.cast());
+
+        if class.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_method_get_class"));
+        }
+
+        Ok(UnityClass {
+            inner: class.cast(),
+        })
+    }
+
+    fn get_method_param_count(&self, method: &UnityMethod) -> Result<usize, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_signature_get_param_count
+            .ok_or(RuntimeError::MissingFunction("mono_signature_get_param_count"))?;
+
+        let signature = self.get_method_signature(method)?;
+
+        Ok(function(signature) as usize)
+    }
+
+    fn get_method_param_types(&self, method: &UnityMethod) -> Result<Vec<UnityType>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_signature_get_params
+            .ok_or(RuntimeError::MissingFunction("mono_signature_get_params"))?;
+
+        let signature = self.get_method_signature(method)?;
+
+        Ok(collect_iter(|iter| function(signature, iter))
+            .into_iter()
+            .map(|ty| UnityType { inner: ty.cast() })
+            .collect())
+    }
+
+    fn get_method_param_names(&self, method: &UnityMethod) -> Result<Vec<String>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_method_get_param_names
+            .ok_or(RuntimeError::MissingFunction("mono_method_get_param_names"))?;
+
+        let count = self.get_method_param_count(method)?;
+
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut names: Vec<*const c_char> = vec![std::ptr::null(); count];
+
+        function(method.inner.cast(), names.as_mut_ptr());
+
+        names
+            .into_iter()
+            .map(|name| match name.is_null() {
+                false => Ok(unsafe { CStr::from_ptr(name) }.to_str()?.to_string()),
+                true => Err(RuntimeError::ReturnedNull("mono_method_get_param_names")),
+            })
+            .collect()
+    }
+
+    fn get_method_return_type(&self, method: &UnityMethod) -> Result<UnityType, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_signature_get_return_type
+            .ok_or(RuntimeError::MissingFunction("mono_signature_get_return_type"))?;
+
+        let signature = self.get_method_signature(method)?;
+
+        let ty = function(signature);
+
+        if ty.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_signature_get_return_type"));
+        }
+
+        Ok(UnityType { inner: ty.cast() })
+    }
+
+    fn get_method_flags(&self, method: &UnityMethod) -> Result<i32, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_method_get_flags
+            .ok_or(RuntimeError::MissingFunction("mono_method_get_flags"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        let mut iflags: u32 = 0;
+
+        Ok(function(method.inner.cast(), &mut iflags) as i32)
+    }
+
+    fn get_method_object(&self, method: &UnityMethod) -> Result<UnityObject, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_method_get_object
+            .ok_or(RuntimeError::MissingFunction("mono_method_get_object"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        let object = function(self.get_domain()?.inner.cast(), method.inner.cast(), std::ptr::null_mut());
+
+        if object.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_method_get_object"));
+        }
+
+        Ok(UnityObject {
+            inner: object.cast(),
+        })
+    }
+
+    fn get_object_class(&self, object: &UnityObject) -> Result<UnityClass, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_object_get_class
+            .ok_or(RuntimeError::MissingFunction("mono_object_get_class"))?;
+
+        if object.inner.is_null() {
+            return Err(RuntimeError::NullPointer("object"));
+        }
+
+        let class = function(object.inner.cast());
+
+        if class.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_object_get_class"));
+        }
+
+        Ok(UnityClass {
+            inner: class.cast(),
+        })
+    }
+
+    fn get_array_length(&self, array: &UnityObject) -> Result<usize, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_array_length
+            .ok_or(RuntimeError::MissingFunction("mono_array_length"))?;
+
+        if array.inner.is_null() {
+            return Err(RuntimeError::NullPointer("array"));
+        }
+
+        Ok(function(array.inner.cast()))
+    }
+
+    fn get_image_name(&self, image: &UnityImage) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_image_get_name
+            .ok_or(RuntimeError::MissingFunction("mono_image_get_name"))?;
+
+        if image.inner.is_null() {
+            return Err(RuntimeError::NullPointer("image"));
+        }
+
+        let name = function(image.inner.cast());
+
+        if name.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_image_get_name"));
+        }
+
+        let name = unsafe { CStr::from_ptr(name) }.to_str()?;
+
+        Ok(name.to_string())
+    }
+
+    fn get_image_filename(&self, image: &UnityImage) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_image_get_filename
+            .ok_or(RuntimeError::MissingFunction("mono_image_get_filename"))?;
+
+        if image.inner.is_null() {
+            return Err(RuntimeError::NullPointer("image"));
+        }
+
+        let name = function(image.inner.cast());
+
+        if name.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_image_get_filename"));
+        }
+
+        let name = unsafe { CStr::from_ptr(name) }.to_str()?;
+
+        Ok(name.to_string())
+    }
+
+    fn get_image_assembly(&self, image: &UnityImage) -> Result<UnityAssembly, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_image_get_assembly
+            .ok_or(RuntimeError::MissingFunction("mono_image_get_assembly"))?;
+
+        if image.inner.is_null() {
+            return Err(RuntimeError::NullPointer("image"));
+        }
+
+        let assembly = function(image.inner.cast());
+
+        if assembly.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_image_get_assembly"));
+        }
+
+        Ok(UnityAssembly {
+            inner: assembly.cast(),
+        })
+    }
+
+    /// walks the TypeDef metadata table, row 1 is the `<Module>` pseudo type
+    fn get_image_classes(&self, image: &UnityImage) -> Result<Vec<UnityClass>, RuntimeError> {
+        let get_rows = &self
+            .exports
+            .clone()
+            .mono_image_get_table_rows
+            .ok_or(RuntimeError::MissingFunction("mono_image_get_table_rows"))?;
+
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_get
+            .ok_or(RuntimeError::MissingFunction("mono_class_get"))?;
+
+        if image.inner.is_null() {
+            return Err(RuntimeError::NullPointer("image"));
+        }
+
+        let rows = get_rows(image.inner.cast(), MONO_TABLE_TYPEDEF);
+
+        Ok((1..=rows.max(0) as u32)
+            .map(|row| function(image.inner.cast(), MONO_TOKEN_TYPE_DEF | row))
+            .filter(|class| !class.is_null())
+            .map(|class| UnityClass {
+                inner: class.cast(),
+            })
+            .collect())
+    }
+
+    fn string_to_utf8(&self, string: &UnityString) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_string_to_utf8
+            .ok_or(RuntimeError::MissingFunction("mono_string_to_utf8"))?;
+
+        if string.inner.is_null() {
+            return Err(RuntimeError::NullPointer("string"));
+        }
+
+        let chars = function(string.inner.cast());
+
+        if chars.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_string_to_utf8"));
+        }
+
+        // freed before the utf-8 check, which may fail
+        let value = unsafe { CStr::from_ptr(chars) }.to_str().map(str::to_string);
+
+        self.free(chars.cast_mut().cast())?;
+
+        Ok(value?)
+    }
+
+    fn get_type_class(&self, ty: &UnityType) -> Result<UnityClass, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_from_mono_type
+            .ok_or(RuntimeError::MissingFunction("mono_class_from_mono_type"))?;
+
+        if ty.inner.is_null() {
+            return Err(RuntimeError::NullPointer("type"));
+        }
+
+        let class = function(ty.inner.cast());
+
+        if class.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_class_from_mono_type"));
+        }
+
+        Ok(UnityClass {
+            inner: class.cast(),
+        })
+    }
+
+    fn is_type_byref(&self, ty: &UnityType) -> Result<bool, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_type_is_byref
+            .ok_or(RuntimeError::MissingFunction("mono_type_is_byref"))?;
+
+        if ty.inner.is_null() {
+            return Err(RuntimeError::NullPointer("type"));
+        }
+
+        Ok(function(ty.inner.cast()) != 0)
+    }
+
+    fn is_class_valuetype(&self, class: &UnityClass) -> Result<bool, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_is_valuetype
+            .ok_or(RuntimeError::MissingFunction("mono_class_is_valuetype"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(function(class.inner.cast()) != 0)
+    }
+
+    fn is_class_enum(&self, class: &UnityClass) -> Result<bool, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_is_enum
+            .ok_or(RuntimeError::MissingFunction("mono_class_is_enum"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        Ok(function(class.inner.cast()) != 0)
+    }
+
+    fn is_class_assignable_from(&self, class: &UnityClass, other: &UnityClass) -> Result<bool, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_is_assignable_from
+            .ok_or(RuntimeError::MissingFunction("mono_class_is_assignable_from"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        if other.inner.is_null() {
+            return Err(RuntimeError::NullPointer("other"));
+        }
+
+        Ok(function(class.inner.cast(), other.inner.cast()) != 0)
+    }
+
+    fn get_class_value_size(&self, class: &UnityClass) -> Result<usize, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_class_value_size
+            .ok_or(RuntimeError::MissingFunction("mono_class_value_size"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        let mut align = 0;
+
+        Ok(function(class.inner.cast(), &mut align) as usize)
+    }
+
+    fn get_assembly_version(&self, assembly: &UnityAssembly) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_assembly_get_name
+            .ok_or(RuntimeError::MissingFunction("mono_assembly_get_name"))?;
+
+        if assembly.inner.is_null() {
+            return Err(RuntimeError::NullPointer("assembly"));
+        }
+
+        let name = function(assembly.inner.cast());
+
+        if name.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_assembly_get_name"));
+        }
+
+        let name = unsafe { &*name };
+
+        Ok(format!(
+            "{}.{}.{}.{}",
+            name.major, name.minor, name.build, name.revision
+        ))
+    }
+
+    fn get_image_guid(&self, image: &UnityImage) -> Result<String, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_image_get_guid
+            .ok_or(RuntimeError::MissingFunction("mono_image_get_guid"))?;
+
+        if image.inner.is_null() {
+            return Err(RuntimeError::NullPointer("image"));
+        }
+
+        let guid = function(image.inner.cast());
+
+        if guid.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_image_get_guid"));
+        }
+
+        let guid = unsafe { CStr::from_ptr(guid) }.to_str()?;
+
+        Ok(guid.to_string())
+    }
+
+    fn try_invoke_method(
+        &self,
+        method: &UnityMethod,
+        obj: Option<&UnityObject>,
+        params: Option<&mut Vec<*mut c_void>>,
+    ) -> Result<Option<UnityObject>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_runtime_invoke
+            .ok_or(RuntimeError::MissingFunction("mono_runtime_invoke"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        let mut exc: *mut MonoObject = std::ptr::null_mut();
+        let object = match obj {
+            Some(obj) => obj.inner,
+            None => std::ptr::null_mut(),
+        };
+
+        let params = match params {
+            Some(params) if !params.is_empty() => params.as_mut_ptr(),
+            _ => std::ptr::null_mut(),
+        };
+
+        let result = function(method.inner.cast(), object.cast(), params, &mut exc);
+
+        if !exc.is_null() {
+            return Err(RuntimeError::ManagedException(self.format_exception(exc)));
+        }
+
+        match result.is_null() {
+            false => Ok(Some(UnityObject {
+                inner: result.cast(),
+            })),
+            true => Ok(None),
+        }
+    }
+
+    fn new_object(&self, class: &UnityClass) -> Result<UnityObject, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_object_new
+            .ok_or(RuntimeError::MissingFunction("mono_object_new"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        let object = function(self.get_domain()?.inner.cast(), class.inner.cast());
+
+        if object.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_object_new"));
+        }
+
+        Ok(UnityObject {
+            inner: object.cast(),
+        })
+    }
+
+    fn new_gc_handle(&self, object: &UnityObject, pinned: bool) -> Result<u32, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_gchandle_new
+            .ok_or(RuntimeError::MissingFunction("mono_gchandle_new"))?;
+
+        if object.inner.is_null() {
+            return Err(RuntimeError::NullPointer("object"));
+        }
+
+        Ok(function(object.inner.cast(), pinned as c_int))
+    }
+
+    fn free_gc_handle(&self, handle: u32) -> Result<(), RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_gchandle_free
+            .ok_or(RuntimeError::MissingFunction("mono_gchandle_free"))?;
+
+        function(handle);
+
+        Ok(())
+    }
+
+    fn get_class_attributes(&self, class: &UnityClass) -> Result<Vec<UnityClass>, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_custom_attrs_from_class
+            .ok_or(RuntimeError::MissingFunction("mono_custom_attrs_from_class"))?;
+
+        let free = &self
+            .exports
+            .clone()
+            .mono_custom_attrs_free
+            .ok_or(RuntimeError::MissingFunction("mono_custom_attrs_free"))?;
+
+        if class.inner.is_null() {
+            return Err(RuntimeError::NullPointer("class"));
+        }
+
+        let info = function(class.inner.cast());
+
+        // classes without attributes have no attribute info at all
+        if info.is_null() {
+            return Ok(Vec::new());
+        }
+
+        let entries = unsafe {
+            std::slice::from_raw_parts((*info).attrs.as_ptr(), (*info).num_attrs.max(0) as usize)
+        };
+
+        // the class of an attribute is the one declaring its constructor
+        let classes = entries
+            .iter()
+            .filter(|entry| !entry.ctor.is_null())
+            .map(|entry| {
+                self.get_method_class(&UnityMethod {
+                    inner: entry.ctor.cast(),
+                })
+            })
+            .collect();
+
+        free(info);
+
+        classes
+    }
+
+    fn compile_method(&self, method: &UnityMethod) -> Result<MethodPointer, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_compile_method
+            .ok_or(RuntimeError::MissingFunction("mono_compile_method"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        // jit compiles the method if it hasn't run yet, otherwise returns the existing code
+        let code = function(method.inner.cast());
+
+        if code.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_compile_method"));
+        }
+
+        Ok(code)
+    }
+
+    fn get_method_generic_arity(&self, method: &UnityMethod) -> Result<usize, RuntimeError> {
+        let get_token = &self
+            .exports
+            .clone()
+            .mono_method_get_token
+            .ok_or(RuntimeError::MissingFunction("mono_method_get_token"))?;
+
+        let get_image = &self
+            .exports
+            .clone()
+            .mono_class_get_image
+            .ok_or(RuntimeError::MissingFunction("mono_class_get_image"))?;
+
+        let get_table = &self
+            .exports
+            .clone()
+            .mono_image_get_table_info
+            .ok_or(RuntimeError::MissingFunction("mono_image_get_table_info"))?;
+
+        let get_rows = &self
+            .exports
+            .clone()
+            .mono_table_info_get_rows
+            .ok_or(RuntimeError::MissingFunction("mono_table_info_get_rows"))?;
+
+        let decode = &self
+            .exports
+            .clone()
+            .mono_metadata_decode_row_col
+            .ok_or(RuntimeError::MissingFunction("mono_metadata_decode_row_col"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        let class = self.get_method_class(method)?;
+        let image = get_image(class.inner.cast());
+
+        if image.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_class_get_image"));
+        }
+
+        let table = get_table(image, MONO_TABLE_GENERICPARAM);
+
+        if table.is_null() {
+            return Err(RuntimeError::ReturnedNull("mono_image_get_table_info"));
+        }
+
+        // the row in the MethodDef table, 0 for methods that aren't in the metadata
+        let row = get_token(method.inner.cast()) & 0x00ffffff;
+
+        if row == 0 {
+            return Ok(0);
+        }
+
+        // the method's generic parameters name it as their owner, and the table is sorted by
+        // owner, so they're the rows between the first owner that isn't lower and the first
+        // that's higher
+        let owner = (row << 1) | MONO_TYPEORMETHOD_METHOD;
+        let rows = get_rows(table).max(0);
+
+        let bound = |past: fn(Ordering) -> bool| {
+            let (mut low, mut high) = (0, rows);
+
+            while low < high {
+                let middle = low + (high - low) / 2;
+
+                match past(decode(table, middle, MONO_GENERICPARAM_OWNER).cmp(&owner)) {
+                    true => high = middle,
+                    false => low = middle + 1,
+                }
+            }
+
+            low
+        };
+
+        let first = bound(|ordering| ordering != Ordering::Less);
+        let last = bound(|ordering| ordering == Ordering::Greater);
+
+        Ok((last - first) as usize)
+    }
+
+    fn is_method_inflated(&self, method: &UnityMethod) -> Result<bool, RuntimeError> {
+        let function = &self
+            .exports
+            .clone()
+            .mono_method_get_context
+            .ok_or(RuntimeError::MissingFunction("mono_method_get_context"))?;
+
+        if method.inner.is_null() {
+            return Err(RuntimeError::NullPointer("method"));
+        }
+
+        // only inflated methods have a generic context
+        Ok(!function(method.inner.cast()).is_null())
+    }
 }
 
 extern "C" fn enumerate_assemblies(assembly: *mut MonoAssembly, data: *mut c_void) {
@@ -32,11 +32,37 @@ pub struct MonoAssembly {}
 #[repr(C)]
 pub struct MonoProperty {}
 
+/// a field
+#[derive(Debug)]
+#[repr(C)]
+pub struct MonoClassField {}
+
+/// a type
+#[derive(Debug)]
+#[repr(C)]
+pub struct MonoType {}
+
+/// a method signature
+#[derive(Debug)]
+#[repr(C)]
+pub struct MonoMethodSignature {}
+
+/// a class vtable, holds the static data of a class
+#[derive(Debug)]
+#[repr(C)]
+pub struct MonoVTable {}
+
 
 /// a mono image
 #[derive(Debug)]
 #[repr(C)]
 pub struct MonoImage {}
+
+/// a metadata table of an image
+#[derive(Debug)]
+#[repr(C)]
+pub struct MonoTableInfo {}
+
 /// a mono string
 #[derive(Debug)]
 #[repr(C)]
@@ -85,3 +111,22 @@ pub struct AssemblyName {
     pub revision: c_ushort,
     pub arch: u32,
 }
+
+/// a custom attribute, as stored in `MonoCustomAttrInfo`
+#[derive(Debug)]
+#[repr(C)]
+pub struct MonoCustomAttrEntry {
+    pub ctor: *mut MonoMethod,
+    pub data_size: u32,
+    pub data: *const u8,
+}
+
+/// the custom attributes of a class, method or assembly, `attrs` holds `num_attrs` entries
+#[derive(Debug)]
+#[repr(C)]
+pub struct MonoCustomAttrInfo {
+    pub num_attrs: c_int,
+    pub cached: c_int,
+    pub image: *mut MonoImage,
+    pub attrs: [MonoCustomAttrEntry; 0],
+}
@@ -19,6 +19,7 @@ use crate::{
         object::UnityObject,
         string::UnityString,
         thread::UnityThread, class::UnityClass, image::UnityImage, property::UnityProperty,
+        field::UnityField, ty::UnityType,
     },
     il2cpp::Il2Cpp,
     libs::{self},
@@ -73,6 +74,14 @@ pub enum RuntimeError {
     NullPointer(&'static str),
     #[error("Not Implemented: {0}")]
     NotImplemented(&'static str),
+    #[error("Managed exception: {0}")]
+    ManagedException(String),
+    #[error("Field {0} is {1} bytes, but was read as {2} bytes")]
+    FieldSizeMismatch(String, usize, usize),
+    #[error("Assembly {0} not found")]
+    AssemblyNotFound(String),
+    #[error("Class {0} not found")]
+    ClassNotFound(String),
 }
 
 #[derive(Debug)]
@@ -136,6 +145,64 @@ pub trait Runtime {
     fn get_method(&self, name: &str, args: i32, class: &UnityClass) -> Result<UnityMethod, RuntimeError>;
     fn get_assembly_object(&self, assembly: &UnityAssembly) -> Result<UnityObject, RuntimeError>;
     fn unbox_object(&self, object: &UnityObject) -> Result<UnityObject, RuntimeError>;
+    fn box_value(&self, class: &UnityClass, value: *mut c_void) -> Result<UnityObject, RuntimeError>;
+    fn get_field(&self, class: &UnityClass, name: &str) -> Result<UnityField, RuntimeError>;
+    fn get_field_name(&self, field: &UnityField) -> Result<String, RuntimeError>;
+    fn get_field_type(&self, field: &UnityField) -> Result<UnityType, RuntimeError>;
+    fn get_field_offset(&self, field: &UnityField) -> Result<usize, RuntimeError>;
+    fn get_field_parent(&self, field: &UnityField) -> Result<UnityClass, RuntimeError>;
+    fn get_field_flags(&self, field: &UnityField) -> Result<i32, RuntimeError>;
+    fn get_field_value(&self, field: &UnityField, object: &UnityObject, value: *mut c_void) -> Result<(), RuntimeError>;
+    fn set_field_value(&self, field: &UnityField, object: &UnityObject, value: *mut c_void) -> Result<(), RuntimeError>;
+    fn get_static_field_value(&self, field: &UnityField, value: *mut c_void) -> Result<(), RuntimeError>;
+    fn set_static_field_value(&self, field: &UnityField, value: *mut c_void) -> Result<(), RuntimeError>;
+    fn get_type_name(&self, ty: &UnityType) -> Result<String, RuntimeError>;
+    fn get_class_namespace(&self, class: &UnityClass) -> Result<String, RuntimeError>;
+    fn get_class_parent(&self, class: &UnityClass) -> Result<Option<UnityClass>, RuntimeError>;
+    fn get_class_methods(&self, class: &UnityClass) -> Result<Vec<UnityMethod>, RuntimeError>;
+    fn get_class_fields(&self, class: &UnityClass) -> Result<Vec<UnityField>, RuntimeError>;
+    fn get_class_properties(&self, class: &UnityClass) -> Result<Vec<UnityProperty>, RuntimeError>;
+    fn get_class_nested_types(&self, class: &UnityClass) -> Result<Vec<UnityClass>, RuntimeError>;
+    fn get_class_interfaces(&self, class: &UnityClass) -> Result<Vec<UnityClass>, RuntimeError>;
+    fn get_method_class(&self, method: &UnityMethod) -> Result<UnityClass, RuntimeError>;
+    fn get_method_param_count(&self, method: &UnityMethod) -> Result<usize, RuntimeError>;
+    fn get_method_param_types(&self, method: &UnityMethod) -> Result<Vec<UnityType>, RuntimeError>;
+    fn get_method_param_names(&self, method: &UnityMethod) -> Result<Vec<String>, RuntimeError>;
+    fn get_method_return_type(&self, method: &UnityMethod) -> Result<UnityType, RuntimeError>;
+    fn get_method_flags(&self, method: &UnityMethod) -> Result<i32, RuntimeError>;
+    fn get_method_object(&self, method: &UnityMethod) -> Result<UnityObject, RuntimeError>;
+    fn get_object_class(&self, object: &UnityObject) -> Result<UnityClass, RuntimeError>;
+    fn get_array_length(&self, array: &UnityObject) -> Result<usize, RuntimeError>;
+    fn get_image_name(&self, image: &UnityImage) -> Result<String, RuntimeError>;
+    fn get_image_filename(&self, image: &UnityImage) -> Result<String, RuntimeError>;
+    fn get_image_assembly(&self, image: &UnityImage) -> Result<UnityAssembly, RuntimeError>;
+    fn get_image_classes(&self, image: &UnityImage) -> Result<Vec<UnityClass>, RuntimeError>;
+    fn string_to_utf8(&self, string: &UnityString) -> Result<String, RuntimeError>;
+    fn get_type_class(&self, ty: &UnityType) -> Result<UnityClass, RuntimeError>;
+    fn is_type_byref(&self, ty: &UnityType) -> Result<bool, RuntimeError>;
+    fn is_class_valuetype(&self, class: &UnityClass) -> Result<bool, RuntimeError>;
+    fn is_class_enum(&self, class: &UnityClass) -> Result<bool, RuntimeError>;
+    /// whether an instance of `other` can be assigned to a variable of type `class`
+    fn is_class_assignable_from(&self, class: &UnityClass, other: &UnityClass) -> Result<bool, RuntimeError>;
+    /// the size of an unboxed instance of a value type
+    fn get_class_value_size(&self, class: &UnityClass) -> Result<usize, RuntimeError>;
+    fn get_assembly_version(&self, assembly: &UnityAssembly) -> Result<String, RuntimeError>;
+    fn get_image_guid(&self, image: &UnityImage) -> Result<String, RuntimeError>;
+    fn try_invoke_method(
+        &self,
+        method: &UnityMethod,
+        obj: Option<&UnityObject>,
+        params: Option<&mut Vec<*mut c_void>>,
+    ) -> Result<Option<UnityObject>, RuntimeError>;
+    fn new_object(&self, class: &UnityClass) -> Result<UnityObject, RuntimeError>;
+    fn new_gc_handle(&self, object: &UnityObject, pinned: bool) -> Result<u32, RuntimeError>;
+    fn free_gc_handle(&self, handle: u32) -> Result<(), RuntimeError>;
+    fn get_class_attributes(&self, class: &UnityClass) -> Result<Vec<UnityClass>, RuntimeError>;
+    fn compile_method(&self, method: &UnityMethod) -> Result<MethodPointer, RuntimeError>;
+    /// the amount of generic parameters the method has of its own, not counting its class'
+    fn get_method_generic_arity(&self, method: &UnityMethod) -> Result<usize, RuntimeError>;
+    /// whether the method belongs to an instantiation, like `Foo<int>`, rather than a definition
+    fn is_method_inflated(&self, method: &UnityMethod) -> Result<bool, RuntimeError>;
 }
 
 /// looks up the runtime
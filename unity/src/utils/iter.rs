@@ -0,0 +1,19 @@
+use std::ffi::c_void;
+
+/// drains a `void **iter` style enumerator, as used by mono and il2cpp, until it returns null
+pub fn collect_iter<T>(mut next: impl FnMut(*mut *mut c_void) -> *mut T) -> Vec<*mut T> {
+    let mut iter: *mut c_void = std::ptr::null_mut();
+    let mut items = Vec::new();
+
+    loop {
+        let item = next(&mut iter);
+
+        if item.is_null() {
+            break;
+        }
+
+        items.push(item);
+    }
+
+    items
+}
@@ -1,2 +1,3 @@
 pub mod path;
-pub mod libs;
\ No newline at end of file
+pub mod libs;
+pub mod iter;
\ No newline at end of file